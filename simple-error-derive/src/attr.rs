@@ -0,0 +1,453 @@
+use proc_macro2::Span;
+use simple_error::{Delimiters, Severity};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    spanned::Spanned,
+    Attribute, Error, Expr, ExprLit, Fields, Ident, Lit, LitInt, LitStr, Meta, Path, Token,
+    Variant,
+};
+
+use crate::{case::RenameRule, options::Options};
+
+/// The parsed `#[error(...)]` attribute of a variant.
+pub(crate) struct ErrorAttr {
+    /// The span of the attribute, used to report errors.
+    pub span: Span,
+
+    /// The format string.
+    pub literal: LitStr,
+
+    /// Expressions passed as positional arguments, e.g. `#[error("{0}", self.code())]`.
+    pub positional_args: Vec<Expr>,
+
+    /// Expressions passed as named arguments, e.g. `#[error("{label}", label = self.label())]`.
+    pub named_args: Vec<(Ident, Expr)>,
+
+    /// The severity of the variant, e.g. `#[error("...", severity = "warn")]`.
+    pub severity: Option<Severity>,
+
+    /// The constant the message is read from, e.g. `#[error(ref = messages::TIMEOUT)]`.
+    pub message: Option<Path>,
+
+    /// Whether every field must be used by the message, e.g. `#[error("...", strict)]`.
+    pub strict: bool,
+}
+
+/// The content of the `#[error(...)]` attribute.
+struct ErrorArgs {
+    literal: LitStr,
+    positional_args: Vec<Expr>,
+    named_args: Vec<(Ident, Expr)>,
+    severity: Option<Severity>,
+    message: Option<Path>,
+    strict: bool,
+}
+
+impl ErrorAttr {
+    /// Find and parse the `#[error(...)]` attribute of the variant, also accepting the
+    /// `#[error = "..."]` form, which takes no arguments. With `by_name` or `debug_fallback`,
+    /// a variant without the attribute displays its name or `Debug` output instead.
+    /// With `concat_errors`, the format strings of stacked attributes are joined by a space.
+    /// A unit variant displayed by `"{:?}"` alone is displayed through its `Debug` output.
+    pub fn from_variant(variant: &Variant, options: &Options) -> syn::Result<Self> {
+        let mut attrs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("error"));
+        let Some(attr) = attrs.next() else {
+            if options.by_name {
+                return Ok(Self::by_name(variant, options.rename_all));
+            }
+
+            if options.debug_fallback {
+                return Ok(Self::debug(variant, options.delimiters));
+            }
+
+            return Err(Error::new(
+                variant.span(),
+                "Missing #[error(...)] attribute",
+            ));
+        };
+
+        let keyword = ["transparent", "debug"].into_iter().find_map(|keyword| {
+            let attr = variant
+                .attrs
+                .iter()
+                .find(|attr| is_keyword(attr, keyword))?;
+            Some((keyword, attr))
+        });
+        if let (Some((keyword, keyword_attr)), Some(_)) = (keyword, attrs.clone().next()) {
+            return Err(Error::new(
+                keyword_attr.span(),
+                format!(
+                    "#[error({keyword})] can't be combined with another #[error(...)] attribute"
+                ),
+            ));
+        }
+
+        if is_transparent(attr) {
+            return Self::single_field(variant, attr, options.delimiters, "transparent", "");
+        }
+
+        if is_keyword(attr, "debug") {
+            return Self::single_field(variant, attr, options.delimiters, "debug", ":?");
+        }
+
+        let mut error_attr = Self::parse(attr, options.delimiters)?;
+        for attr in attrs {
+            if !options.concat_errors {
+                return Err(Error::new(
+                    attr.span(),
+                    "Only one #[error(...)] attribute is allowed per variant, \
+                     use #[simple_error(concat_errors)] to join them",
+                ));
+            }
+
+            let stacked = Self::parse(attr, options.delimiters)?;
+            error_attr.literal = LitStr::new(
+                &format!("{} {}", error_attr.literal.value(), stacked.literal.value()),
+                error_attr.literal.span(),
+            );
+            error_attr.positional_args.extend(stacked.positional_args);
+            error_attr.named_args.extend(stacked.named_args);
+            error_attr.severity = error_attr.severity.or(stacked.severity);
+            error_attr.message = None;
+            error_attr.strict |= stacked.strict;
+        }
+
+        // A unit variant has no field for `{:?}` to refer to, so it debug-prints the variant.
+        if matches!(variant.fields, Fields::Unit)
+            && error_attr.literal.value() == placeholder(options.delimiters, ":?")
+            && error_attr.positional_args.is_empty()
+        {
+            error_attr.positional_args.push(parse_quote!(self));
+        }
+
+        Ok(error_attr)
+    }
+
+    /// Parse a single `#[error(...)]` or `#[error = "..."]` attribute.
+    fn parse(attr: &Attribute, delimiters: Delimiters) -> syn::Result<Self> {
+        let ErrorArgs {
+            literal,
+            positional_args,
+            named_args,
+            severity,
+            message,
+            strict,
+        } = match &attr.meta {
+            Meta::NameValue(meta) => ErrorArgs {
+                literal: string_literal(&meta.value)?,
+                positional_args: vec![],
+                named_args: vec![],
+                severity: None,
+                message: None,
+                strict: false,
+            },
+            _ => attr.parse_args()?,
+        };
+
+        // The constant is displayed by the only placeholder, written with the delimiters.
+        let literal = match &message {
+            Some(path) => LitStr::new(&placeholder(delimiters, ""), path.span()),
+            None => literal,
+        };
+
+        Ok(Self {
+            span: attr.span(),
+            literal,
+            positional_args,
+            named_args,
+            severity,
+            message,
+            strict,
+        })
+    }
+}
+
+impl ErrorAttr {
+    /// Display the only field of the variant with the given spec, for `#[error(transparent)]`
+    /// and `#[error(debug)]`.
+    fn single_field(
+        variant: &Variant,
+        attr: &Attribute,
+        delimiters: Delimiters,
+        keyword: &str,
+        spec: &str,
+    ) -> syn::Result<Self> {
+        let mut fields = variant.fields.iter();
+        let (Some(field), None) = (fields.next(), fields.next()) else {
+            return Err(Error::new(
+                attr.span(),
+                format!("#[error({keyword})] requires the variant to have exactly one field"),
+            ));
+        };
+
+        let placeholder = match &field.ident {
+            Some(ident) => placeholder(delimiters, &format!("{ident}{spec}")),
+            None => placeholder(delimiters, &format!("0{spec}")),
+        };
+
+        Ok(Self {
+            span: attr.span(),
+            literal: LitStr::new(&placeholder, attr.span()),
+            positional_args: vec![],
+            named_args: vec![],
+            severity: None,
+            message: None,
+            strict: false,
+        })
+    }
+}
+
+impl ErrorAttr {
+    /// Display the name of the variant, e.g. `Red` for `Color::Red`, converted to
+    /// the given case if any.
+    fn by_name(variant: &Variant, rename_all: Option<RenameRule>) -> Self {
+        let mut name = variant.ident.unraw().to_string();
+        if let Some(rule) = rename_all {
+            name = rule.apply(&name);
+        }
+
+        Self {
+            span: variant.ident.span(),
+            literal: LitStr::new(&name, variant.ident.span()),
+            positional_args: vec![],
+            named_args: vec![],
+            severity: None,
+            message: None,
+            strict: false,
+        }
+    }
+}
+
+impl ErrorAttr {
+    /// Display the variant through its `Debug` implementation.
+    fn debug(variant: &Variant, delimiters: Delimiters) -> Self {
+        Self {
+            span: variant.ident.span(),
+            literal: LitStr::new(&placeholder(delimiters, ":?"), variant.ident.span()),
+            positional_args: vec![parse_quote!(self)],
+            named_args: vec![],
+            severity: None,
+            message: None,
+            strict: false,
+        }
+    }
+}
+
+impl Parse for ErrorArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `transparent` and `debug` display the only field as-is, so they stand alone.
+        let fork = input.fork();
+        if let Ok(keyword) = fork.parse::<Ident>() {
+            if (keyword == "transparent" || keyword == "debug") && fork.parse::<Token![,]>().is_ok()
+            {
+                let other = if fork.peek(LitStr) {
+                    "a format string"
+                } else {
+                    "arguments"
+                };
+                return Err(Error::new(
+                    keyword.span(),
+                    format!("#[error({keyword})] can't be combined with {other}"),
+                ));
+            }
+        }
+
+        // A constant can't be read by the derive, so it's displayed as the only argument, by
+        // a placeholder written once the delimiters are known.
+        let (literal, message) = if input.peek(Token![ref]) {
+            input.parse::<Token![ref]>()?;
+            input.parse::<Token![=]>()?;
+            let path = input.parse::<Path>()?;
+            (LitStr::new("", path.span()), Some(path))
+        } else if input.peek(Ident)
+            && input.peek2(Token![=])
+            && input.fork().parse::<Ident>()? == "include"
+        {
+            input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            (included_literal(&input.parse()?)?, None)
+        } else {
+            (string_literal(&input.parse()?)?, None)
+        };
+
+        // Arguments follow the format string, the same way as for `format!`.
+        let mut positional_args = message
+            .iter()
+            .map(|path| parse_quote!(#path))
+            .collect::<Vec<_>>();
+        let (mut named_args, mut severity, mut strict) = (vec![], None, false);
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            // `strict` requires every field to be used, rather than being an argument.
+            let fork = input.fork();
+            if fork.parse::<Ident>().is_ok_and(|ident| ident == "strict")
+                && (fork.is_empty() || fork.peek(Token![,]))
+            {
+                input.parse::<Ident>()?;
+                strict = true;
+                continue;
+            }
+
+            if input.peek(Ident) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
+                let name = input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+
+                // `severity` sets the metadata of the variant rather than an argument.
+                if name == "severity" {
+                    let literal = input.parse::<LitStr>()?;
+                    let value = Severity::from_name(&literal.value()).ok_or(Error::new(
+                        literal.span(),
+                        r#"Expected one of "trace", "debug", "info", "warn" or "error""#,
+                    ))?;
+                    severity = Some(value);
+                    continue;
+                }
+
+                named_args.push((name, input.parse()?));
+            } else {
+                positional_args.push(input.parse()?);
+            }
+        }
+
+        Ok(Self {
+            literal,
+            positional_args,
+            named_args,
+            severity,
+            message,
+            strict,
+        })
+    }
+}
+
+/// The names given to the fields of a tuple variant by `#[name(0 = "path", 1 = "line")]`,
+/// which the generated code binds them to.
+pub(crate) fn binding_names(variant: &Variant) -> syn::Result<Vec<(usize, Ident)>> {
+    let mut names = vec![];
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("name"))
+    {
+        let Fields::Unnamed(fields) = &variant.fields else {
+            return Err(Error::new(
+                attr.span(),
+                "#[name(...)] only applies to the fields of a tuple variant",
+            ));
+        };
+
+        attr.parse_args_with(|input: ParseStream| {
+            while !input.is_empty() {
+                let index = input.parse::<LitInt>()?;
+                input.parse::<Token![=]>()?;
+                let name = input.parse::<LitStr>()?;
+                let position = index.base10_parse::<usize>()?;
+                if position >= fields.unnamed.len() {
+                    return Err(Error::new(
+                        index.span(),
+                        format!("`{}` has no field {position}", variant.ident),
+                    ));
+                }
+
+                let ident = name.parse::<Ident>()?;
+                if names.iter().any(|(_, other)| *other == ident) {
+                    return Err(Error::new(
+                        name.span(),
+                        format!("`{ident}` already names another field"),
+                    ));
+                }
+                names.push((position, ident));
+
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(names)
+}
+
+/// Whether the attribute is `#[error(transparent)]`, forwarding `Display` and `source()`
+/// to the only field of the variant.
+pub(crate) fn is_transparent(attr: &Attribute) -> bool {
+    is_keyword(attr, "transparent")
+}
+
+/// Whether the attribute is `#[error(keyword)]`, e.g. `#[error(debug)]`.
+fn is_keyword(attr: &Attribute, keyword: &str) -> bool {
+    attr.path().is_ident("error")
+        && attr
+            .parse_args::<Ident>()
+            .is_ok_and(|ident| ident == keyword)
+}
+
+/// Read the format string from the file at the given path, relative to the manifest
+/// directory of the crate, e.g. `#[error(include = "messages/timeout.txt")]`. A single
+/// trailing newline is left out.
+fn included_literal(path: &LitStr) -> syn::Result<LitStr> {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = std::path::Path::new(&manifest_dir).join(path.value());
+    let contents = std::fs::read_to_string(&full_path).map_err(|error| {
+        Error::new(
+            path.span(),
+            format!("Failed to read `{}`: {error}", full_path.display()),
+        )
+    })?;
+
+    let contents = contents.strip_suffix('\n').unwrap_or(&contents);
+    let contents = contents.strip_suffix('\r').unwrap_or(contents);
+    Ok(LitStr::new(contents, path.span()))
+}
+
+/// Extract the format string, which must be a string literal, reporting macro calls,
+/// e.g. `format!(...)`, along with the forms that can be used instead.
+fn string_literal(expr: &Expr) -> syn::Result<LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(literal),
+            ..
+        }) => Ok(literal.clone()),
+        // The derive only sees the tokens of the macro call, not what it expands to.
+        Expr::Macro(call) => {
+            let name = call
+                .mac
+                .path
+                .segments
+                .last()
+                .map_or_else(String::new, |segment| segment.ident.to_string());
+            Err(Error::new(
+                expr.span(),
+                format!(
+                    r#"`{name}!` can't be expanded in #[error(...)] attributes, use a string literal e.g. #[error("error message")], a constant with #[error(ref = MESSAGE)], or a file with #[error(include = "message.txt")]"#
+                ),
+            ))
+        }
+        _ => Err(Error::new(
+            expr.span(),
+            r#"String literal expected in #[error(...)] attribute e.g. #[error("error message")]"#,
+        )),
+    }
+}
+
+/// A placeholder enclosed by the delimiters, e.g. `%0:?%` for `0:?` with `delimiters = "%%"`.
+/// An empty one is given an empty spec when both delimiters are the same, e.g. `%:%`, as
+/// `%%` would be an escaped delimiter.
+fn placeholder(delimiters: Delimiters, content: &str) -> String {
+    let Delimiters { open, close } = delimiters;
+    if content.is_empty() && open == close {
+        return format!("{open}:{close}");
+    }
+
+    format!("{open}{content}{close}")
+}
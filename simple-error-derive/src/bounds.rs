@@ -0,0 +1,107 @@
+use std::collections::BTreeSet;
+
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use simple_error::{Crate, Interpolate};
+use syn::{parse_quote, Field, Generics, Type, WherePredicate};
+
+use crate::source::boxed_type;
+
+/// Infer the bounds needed by the `Display` implementation: every field interpolated
+/// in a format string, whose type mentions one of the enum's type parameters, must
+/// implement the formatting trait of its placeholder.
+pub(crate) fn display_bounds(
+    generics: &Generics,
+    interpolators: &[Interpolate],
+) -> Vec<WherePredicate> {
+    let params = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        return vec![];
+    }
+
+    let (mut seen, mut bounds) = (BTreeSet::new(), vec![]);
+    for interpolator in interpolators {
+        // The type of a nested field, e.g. `{0.code}`, isn't known to the derive.
+        for placeholder in interpolator
+            .placeholders
+            .iter()
+            .filter(|placeholder| placeholder.members.is_empty())
+        {
+            let (Some(field), Some(format_trait)) =
+                (interpolator.field(placeholder), placeholder.format_trait())
+            else {
+                continue;
+            };
+
+            let ty = &field.ty;
+            if !mentions_any(ty, &params) {
+                continue;
+            }
+
+            let bound: WherePredicate = parse_quote!(#ty: #format_trait);
+            if seen.insert(bound.to_token_stream().to_string()) {
+                bounds.push(bound);
+            }
+        }
+    }
+
+    bounds
+}
+
+/// Extend the where clause of the generics with the given bounds.
+pub(crate) fn with_bounds(generics: &Generics, bounds: &[WherePredicate]) -> Generics {
+    let mut generics = generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(bounds.iter().cloned());
+    generics
+}
+
+/// The bounds needed by the `Error` implementation, which requires the enum to be
+/// `Debug` and `Display`, whatever bounds the derives put on its type parameters,
+/// and every source whose type mentions a type parameter to be an `Error` too.
+pub(crate) fn error_bounds(
+    generics: &Generics,
+    krate: Crate,
+    sources: &[&Field],
+) -> Vec<WherePredicate> {
+    let params = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        return vec![];
+    }
+
+    let mut bounds = vec![parse_quote!(Self: #krate::fmt::Debug + #krate::fmt::Display)];
+    let mut seen = BTreeSet::new();
+    // Boxed sources are returned as the value they hold, which is then the one bounded.
+    for ty in sources
+        .iter()
+        .map(|field| boxed_type(&field.ty).unwrap_or(&field.ty))
+    {
+        if mentions_any(ty, &params) && seen.insert(ty.to_token_stream().to_string()) {
+            bounds.push(parse_quote!(#ty: #krate::error::Error + 'static));
+        }
+    }
+    bounds
+}
+
+/// Whether the type refers to any of the given type parameters.
+fn mentions_any(ty: &Type, params: &[&Ident]) -> bool {
+    fn walk(tokens: TokenStream, params: &[&Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => params.contains(&&ident),
+            TokenTree::Group(group) => walk(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    walk(quote!(#ty), params)
+}
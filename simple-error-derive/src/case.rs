@@ -0,0 +1,100 @@
+/// The case variant names are converted to when displayed by name, as set by
+/// `#[simple_error(rename_all = "...")]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RenameRule {
+    /// `lowercase`, e.g. `toomanyrequests`.
+    Lower,
+
+    /// `UPPERCASE`, e.g. `TOOMANYREQUESTS`.
+    Upper,
+
+    /// `snake_case`, e.g. `too_many_requests`.
+    Snake,
+
+    /// `kebab-case`, e.g. `too-many-requests`.
+    Kebab,
+
+    /// `SCREAMING_SNAKE_CASE`, e.g. `TOO_MANY_REQUESTS`.
+    ScreamingSnake,
+
+    /// `Title Case`, e.g. `Too Many Requests`.
+    Title,
+}
+
+impl RenameRule {
+    /// The names of the rules, as written in the attribute.
+    pub const NAMES: &'static [(&'static str, Self)] = &[
+        ("lowercase", Self::Lower),
+        ("UPPERCASE", Self::Upper),
+        ("snake_case", Self::Snake),
+        ("kebab-case", Self::Kebab),
+        ("SCREAMING_SNAKE_CASE", Self::ScreamingSnake),
+        ("Title Case", Self::Title),
+    ];
+
+    /// The rule with the given name, e.g. `snake_case`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(rule, _)| *rule == name)
+            .map(|(_, rule)| *rule)
+    }
+
+    /// Convert the `PascalCase` name of a variant.
+    pub fn apply(&self, name: &str) -> String {
+        let words = words(name);
+        let join = |separator: &str, word: fn(&str) -> String| {
+            words
+                .iter()
+                .map(|w| word(w))
+                .collect::<Vec<_>>()
+                .join(separator)
+        };
+
+        match self {
+            Self::Lower => name.to_lowercase(),
+            Self::Upper => name.to_uppercase(),
+            Self::Snake => join("_", str::to_lowercase),
+            Self::Kebab => join("-", str::to_lowercase),
+            Self::ScreamingSnake => join("_", str::to_uppercase),
+            Self::Title => join(" ", |word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }),
+        }
+    }
+}
+
+/// Split a `PascalCase` name into its words, keeping acronyms together,
+/// e.g. `HTTPError` into `HTTP` and `Error`.
+fn words(name: &str) -> Vec<&str> {
+    let chars = name.char_indices().collect::<Vec<_>>();
+    let (mut words, mut start) = (vec![], 0);
+    for (position, &(index, c)) in chars.iter().enumerate() {
+        if c == '_' {
+            words.push(&name[start..index]);
+            start = index + 1;
+            continue;
+        }
+
+        let previous = position.checked_sub(1).map(|p| chars[p].1);
+        let next = chars.get(position + 1).map(|&(_, c)| c);
+        let is_boundary = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && next.is_some_and(char::is_lowercase))
+            });
+
+        if is_boundary && index > start {
+            words.push(&name[start..index]);
+            start = index;
+        }
+    }
+    words.push(&name[start..]);
+    words.retain(|word| !word.is_empty());
+    words
+}
@@ -0,0 +1,156 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use simple_error::cfg_attrs;
+use syn::{
+    parse_quote, spanned::Spanned, Attribute, DeriveInput, Error, Field, Index, Member, Meta, Type,
+    Variant,
+};
+
+/// Generate the `From` implementations for a variant having a `#[from]` field.
+///
+/// The variant's other fields, if any, are filled with `Default::default()`. Besides
+/// `From<T>` for the field's type, a `String` field also gets `From<&str>` so that catch-all
+/// message variants can be built from string literals.
+///
+/// A boxed `Box<dyn Error>` field only gets `From` for the box: a generic `From<E: Error>`
+/// would conflict with `From<T> for T`, as the enum is an error itself.
+pub(crate) fn impl_from(
+    input: &DeriveInput,
+    variant: &Variant,
+) -> syn::Result<Option<TokenStream>> {
+    let mut from_fields = from_fields(variant);
+    let Some((field, _)) = from_fields.next() else {
+        return Ok(None);
+    };
+
+    if let Some((_, attr)) = from_fields.next() {
+        return Err(Error::new(
+            attr.span(),
+            "#[from] can only be used on a single field of a variant",
+        ));
+    }
+
+    let enum_name = &input.ident;
+    let variant_name = &variant.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let construct = |value: TokenStream| {
+        let fields = variant.fields.iter().enumerate().map(|(index, other)| {
+            let member = match &other.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(index)),
+            };
+
+            // A missing `Default` implementation is reported on the field itself.
+            if std::ptr::eq(other, field) {
+                quote!(#member: #value)
+            } else {
+                quote_spanned!(other.ty.span() => #member: ::core::default::Default::default())
+            }
+        });
+
+        quote!(Self::#variant_name { #(#fields),* })
+    };
+
+    let ty = &field.ty;
+    let cfg_attrs = cfg_attrs(variant).collect::<Vec<_>>();
+    let from_source = construct(quote!(source));
+    let mut impls = quote! {
+        #(#cfg_attrs)*
+        impl #impl_generics ::core::convert::From<#ty> for #enum_name #ty_generics #where_clause {
+            fn from(source: #ty) -> Self {
+                #from_source
+            }
+        }
+    };
+
+    if is_string(ty) {
+        let from_str = construct(quote!(<::std::string::String as ::core::convert::From<
+            &str,
+        >>::from(source)));
+        impls.extend(quote! {
+            #(#cfg_attrs)*
+            impl #impl_generics ::core::convert::From<&str> for #enum_name #ty_generics #where_clause {
+                fn from(source: &str) -> Self {
+                    #from_str
+                }
+            }
+        });
+    }
+
+    Ok(Some(impls))
+}
+
+/// The types the variant's `From` implementations convert from, starting with the type
+/// of its `#[from]` field.
+pub(crate) fn source_types(variant: &Variant) -> Vec<Type> {
+    let Some((field, _)) = from_fields(variant).next() else {
+        return vec![];
+    };
+
+    let mut types = vec![field.ty.clone()];
+    if is_string(&field.ty) {
+        types.push(parse_quote!(&str));
+    }
+    types
+}
+
+/// Whether the field is marked with `#[from(no_source)]`, keeping it from being
+/// returned by `Error::source()`.
+pub(crate) fn hides_source(field: &Field) -> syn::Result<bool> {
+    let mut no_source = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("from"))
+    {
+        if matches!(attr.meta, Meta::Path(_)) {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("no_source") {
+                no_source = true;
+                return Ok(());
+            }
+
+            Err(meta.error("Unsupported option in #[from(...)] attribute"))
+        })?;
+    }
+
+    Ok(no_source)
+}
+
+/// Find the fields marked with `#[from]`, along with the attribute itself.
+fn from_fields(variant: &Variant) -> impl Iterator<Item = (&Field, &Attribute)> {
+    variant.fields.iter().filter_map(|field| {
+        let attr = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("from"))?;
+        Some((field, attr))
+    })
+}
+
+/// Whether the field is marked with `#[from]`, making it the source of the variant, unless
+/// it holds a message, as neither `String` nor `&str` is an error.
+pub(crate) fn implies_source(field: &Field) -> bool {
+    let is_str = matches!(&field.ty, Type::Reference(reference) if matches!(
+        &*reference.elem,
+        Type::Path(path) if path.path.is_ident("str")
+    ));
+    field.attrs.iter().any(|attr| attr.path().is_ident("from")) && !is_string(&field.ty) && !is_str
+}
+
+/// Whether the type is (most likely) `std::string::String`.
+fn is_string(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "String" && segment.arguments.is_empty())
+}
@@ -1,8 +1,21 @@
-use proc_macro2::TokenStream;
-use quote::quote;
-use simple_error::Interpolate;
+mod attr;
+mod bounds;
+mod case;
+mod from;
+mod options;
+mod source;
+
+use attr::{binding_names, ErrorAttr};
+use bounds::{display_bounds, error_bounds, with_bounds};
+use from::{impl_from, source_types};
+use options::Options;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use simple_error::{cfg_attrs, Delimiters, Interpolate};
+use source::{impl_source, source_field, transparent_field};
 use syn::{
-    parse_macro_input, spanned::Spanned, Data::Enum, DataEnum, DeriveInput, Error, Expr, ExprLit,
+    ext::IdentExt, parse_macro_input, parse_quote, punctuated::Punctuated, spanned::Spanned,
+    Attribute, Data::Enum, DataEnum, DeriveInput, Error, Fields, Member, Meta, Token, Variant,
 };
 
 /**
@@ -57,12 +70,215 @@ assert_eq!(
     "Named error: critical error"
 );
 ```
+
+The generated `match` lists every variant explicitly, without a wildcard arm, so a
+variant added without an `#[error(...)]` attribute is caught at compile time:
+
+```rust,compile_fail
+use simple_error_derive::SimpleError;
+
+#[derive(Debug, SimpleError)]
+enum SomeError {
+    #[error("Unit error")]
+    Unit,
+
+    Added,
+}
+```
+
+The format string and its arguments are passed to `write!` as they are, so that a
+placeholder matching neither a field nor an argument is rejected by the compiler
+itself, rather than silently displaying something else:
+
+```rust,compile_fail
+use simple_error_derive::SimpleError;
+
+#[derive(Debug, SimpleError)]
+enum SomeError {
+    #[error("Unnamed error: {0} {missing}")]
+    Unnamed(u8),
+}
+```
+
+A variant can set its severity with `#[error("...", severity = "warn")]`, one of `trace`,
+`debug`, `info`, `warn` or `error`, which makes `severity` a reserved argument name. When any
+variant does, the enum gets a `severity(&self) -> simple_error::Severity` accessor, which
+returns `Severity::Error` for the variants that don't set one.
+
+A format string without arguments can also be written as `#[error = "Unit error"]`.
+
+A variant marked `#[error("...", strict)]` fails to compile unless its message uses every
+one of its fields, other than its source and `#[from]` field, which makes `strict` a
+reserved argument name.
+
+A variant with a single field can be marked `#[error(transparent)]` to forward both
+`Display` and `Error::source()` to that field, e.g. for a variant wrapping another error
+with `#[from]`. It can also be marked `#[error(debug)]` to display the field through
+`Debug`, e.g. for a type without a `Display` implementation. A unit variant marked
+`#[error("{:?}")]` is displayed through the `Debug` implementation of the enum.
+
+The format string can also be read from a file, relative to the directory of the crate's
+manifest, with `#[error(include = "messages/timeout.txt")]`, leaving out a single trailing
+newline. Like other format strings, it can be followed by arguments. As the derive only
+reads the file when the enum is compiled, editing it doesn't recompile the enum by itself.
+
+A message shared between errors can be kept in a `const` and displayed
+with `#[error(ref = messages::TIMEOUT)]`. As the derive can't read the constant, its
+placeholders aren't interpolated, and `message_template()` returns the constant itself.
+
+Like `format!`, the format string can be followed by positional and named arguments,
+e.g. `#[error("{label}: {0}", self.code(), label = self.label())]`. Positional values then
+refer to the positional arguments rather than to the fields of the variant. The arguments
+can use the named fields of the variant, and are only evaluated when the error is displayed.
+
+Widths and precisions can be taken from other fields as well, e.g. `{0:1$}` pads the first
+field to the width held by the second one, and `{value:.prec$}` uses the `prec` field.
+
+Fields named by a raw identifier are referred to the same way, e.g. `{r#type}`.
+
+The fields of a field can be accessed as well, e.g. `{0.code}` or `{state.inner.code}`.
+Methods taking no arguments can be called on them the same way, e.g. `{status.as_u16()}`.
+As the derive doesn't know their type, generic enums need to bound it themselves.
+A field can also be referred to through `self`, e.g. `{self.code}`, the same way as `{code}`.
+
+The fields of a tuple variant can be given the names they are bound to in the generated
+code, to make it read better, with `#[name(0 = "path", 1 = "line")]` on the variant.
+
+Placeholders can also refer to a constant by its path, e.g. `{Self::PREFIX}` for an
+associated constant of the enum.
+
+Besides the standard format specs, the following are applied by the derive itself:
+- `{0:path}`: displays a `Path`/`PathBuf` field through `Path::display`.
+- `{0:join(, )}`: displays every item of a collection field, e.g. a `Vec`, separated by
+  the text in parentheses. An empty collection displays nothing.
+- `{0:yesno}`: displays a `bool` field as `yes` or `no` rather than `true` or `false`.
+- `{0:duration}`: displays a `Duration` field in a human-readable form, e.g. `5s 200ms`.
+
+For generic enums, the `Display` implementation requires every interpolated field whose
+type mentions a type parameter to implement the formatting trait of its placeholder,
+e.g. `T: Display` for `{0}` and `T: Debug` for `{0:?}`.
+
+A variant can mark one of its fields with `#[from]` to generate a `From` implementation
+for the field's type, with its other fields filled by `Default::default()`. A `String`
+field additionally gets `From<&str>`. Each source type can only be claimed by one variant.
+A `Box<dyn Error + Send + Sync>` field only converts from the box, as a generic
+`From<E: Error>` would conflict with `From<T> for T`, the enum being an error itself.
+Other errors can be boxed first, e.g. with `.map_err(Box::from)?`.
+
+`Error::source()` returns the field marked with `#[source]`, or else the `#[from]` field,
+unless it's a `String` or `&str` message, or else the field named `source`, of the variant.
+A `#[from(no_source)]` field only drives the conversion, and is never returned, e.g. for
+a field which isn't an error. The format string can refer to the source as `{source}`,
+whatever the name of its field. A boxed `Box<dyn Error + Send + Sync>` source is returned
+as the error it holds.
+
+Additional code can be generated by setting options on the enum with the
+`#[simple_error(...)]` attribute. Three features generate code using the `simple-error`
+crate, and so require a dependency on it: the `severity` accessor, the `{0:duration}`
+spec and the `chain` option. The options are:
+- `templates`: generates `message_template(&self) -> &'static str`, returning the
+  raw format string of the variant.
+- `render`: moves the formatting into `render(&self, f: &mut Formatter) -> fmt::Result`,
+  which `Display` delegates to. As inherent methods can't be overridden, use
+  `render = path::to::function` to have `Display` call `function(self, f)` instead,
+  which can in turn wrap `render()`.
+- `delimiters = "%%"`: recognizes placeholders enclosed by the given opening and closing
+  characters instead of braces, e.g. `%name%`. Braces are then taken literally.
+- `padded`: renders the message into a `String` before writing it, so that it honors
+  the width, fill, alignment and precision it is formatted with, e.g. `{:>30}`.
+- `index`: generates `variant_index(&self) -> usize`, returning the position of the
+  variant in the enum, in declaration order.
+- `json`: generates `to_json(&self) -> String`, returning the variant name and message
+  as a JSON object, e.g. `{"error":"NotFound","message":"Not found"}`.
+- `assert_send_sync`: fails to compile unless the enum is `Send + Sync`, e.g. for
+  errors crossing threads or `.await` points. The type parameters of generic enums
+  need to be bounded accordingly.
+- `spec(hex = path::to::Wrapper)`: registers the custom spec `{0:hex}`, displaying the
+  value as `Wrapper(&value)` through the `Display` implementation of the wrapper.
+  The wrapper can also be a function returning something displayable, e.g. a `String`.
+- `num = path::to::group_digits`: registers the custom spec `{0:num}`, displaying the value
+  as `group_digits(&value)`, e.g. to format numbers with thousands separators.
+- `chain`: generates `chain(&self) -> simple_error::Chain`, iterating over the error and
+  each of its successive sources.
+- `by_name`: displays the variants without an `#[error(...)]` attribute by their name,
+  e.g. `Red` for `Color::Red`. Add `rename_all = "snake_case"` to convert the names to
+  `lowercase`, `UPPERCASE`, `snake_case`, `kebab-case`, `SCREAMING_SNAKE_CASE` or `Title Case`.
+- `debug_fallback`: displays the variants without an `#[error(...)]` attribute through
+  their `Debug` representation, e.g. `Unnamed(1)`, so the enum must implement `Debug`.
+- `prefix = "[{request_id}] "`: prepends the format string to the message of every
+  variant having all the fields it refers to by name, and leaves the other variants as-is.
+- `inline`: marks the generated `Display::fmt` as `#[inline]`, e.g. for small enums
+  displayed in hot loops.
+- `concat_errors`: joins the format strings of a variant's stacked `#[error(...)]`
+  attributes with a space, e.g. `#[error("Request failed:")] #[error("{0}")]`. Without
+  it, a variant can only have one `#[error(...)]` attribute.
+- `format_into`: generates `format_into(&self, buf: &mut String)`, appending the message
+  to the buffer, e.g. to reuse it across errors.
+- `summary`: generates `summary(&self) -> String`, returning the first line of the
+  message, e.g. for logging contexts expecting single-line messages.
+- `dedup_args`: renders a value formatted by several placeholders, e.g. `{name} ({name})`,
+  into a `String` once, rather than formatting it for each of them. Only values whose
+  placeholders all display them without a spec are, as a spec, e.g. `{0:>9}`, applies to
+  the value itself.
+- `kind`: generates a fieldless `{Enum}Kind` enum with a variant for each of the enum's,
+  along with `kind(&self)`, returning the kind of the variant, and `matches(&self, kind)`,
+  e.g. `error.matches(SomeErrorKind::Timeout)`. Both are `const`.
+- `vis = "pub(crate)"`: sets the visibility of the generated methods, `pub` by default.
+- `catch_all = "Unknown"`: writes the message of the `Unknown` unit variant instead, when
+  displaying a variant fails, e.g. because of a field whose `Display` returns an error.
+  Its message can't have placeholders. The message of the variant is rendered before
+  being written, so nothing of it is written when it fails.
+- `propagate_alternate`: pretty-prints the values of `Debug` placeholders when the error is
+  displayed in alternate mode, e.g. `{0:?}` as `{0:#?}` for `{:#}`.
+- `show_source`: appends ` (caused by: {source})` to the message of every variant with
+  a source, unless it already displays it.
+- `error_trait = my_crate::MyError`: also implements the given error trait, e.g. for an
+  ecosystem using its own, through its `message(&self) -> String` method, which returns
+  the message of the variant.
+- `into_boxed`: generates `into_boxed(self) -> Box<dyn Error + Send + Sync>`, which can be
+  downcast back to the enum. Like `assert_send_sync`, it fails to compile unless the enum
+  is `Send + Sync`.
+- `message_table`: generates `const MESSAGES: &[(&str, &str)]`, listing the name of each
+  variant along with its format string, e.g. for help text.
+- `no_std`: refers to `::core` rather than `::std` in the generated code, e.g. `::core::write!`.
+
+```rust,compile_fail
+use std::rc::Rc;
+
+use simple_error_derive::SimpleError;
+
+#[derive(Debug, SimpleError)]
+#[simple_error(assert_send_sync)]
+enum SharedError {
+    #[error("Shared error: {0}")]
+    Shared(Rc<String>),
+}
+```
+
+A variant displayed by `debug_fallback` fails to compile unless the enum implements `Debug`:
+
+```rust,compile_fail
+use simple_error_derive::SimpleError;
+
+#[derive(SimpleError)]
+#[simple_error(debug_fallback)]
+enum UndocumentedError {
+    Undocumented(u8),
+}
+```
+
+Enums with a primitive `#[repr(...)]` and explicit discriminants also get a
+`discriminant(&self)` accessor, returning the variant's discriminant as the `repr` type.
+
+With the `legacy` feature, the `Error` implementation also provides the deprecated
+`description()`, returning the format string of the variant, for code still calling it.
+As Cargo unifies features, this applies to every enum of the build as soon as any crate
+depending on `simple-error-derive` enables it.
 */
-#[proc_macro_derive(SimpleError, attributes(error))]
+#[proc_macro_derive(SimpleError, attributes(error, simple_error, from, source, name))]
 pub fn thiserror(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     impl_display_error(&parse_macro_input!(input as DeriveInput))
-        .map_err(|e| e.to_compile_error())
-        .unwrap()
+        .unwrap_or_else(|e| e.to_compile_error())
         .into()
 }
 
@@ -72,48 +288,1455 @@ fn impl_display_error(input: &DeriveInput) -> syn::Result<TokenStream> {
         return Err(Error::new(input.span(), "This macro only supports enums"));
     };
 
-    let match_arms = variants
-        .iter()
-        .map(|variant| {
-            let attr = variant
-                .attrs
-                .iter()
-                .find(|attr| attr.path().is_ident("error"))
-                .ok_or(Error::new(
-                    variant.span(),
-                    "Missing #[error(...)] attribute",
-                ))?;
-
-            let Expr::Lit(ExprLit {
-                lit: syn::Lit::Str(literal),
-                ..
-            }) = attr.parse_args::<Expr>()?
-            else {
+    let options = Options::from_attrs(&input.attrs)?;
+    let krate = options.krate;
+    let repr = primitive_repr(&input.attrs)?;
+    let (mut interpolators, mut template_arms, mut discriminant_arms) = (vec![], vec![], vec![]);
+    let (mut name_arms, mut index_arms) = (vec![], vec![]);
+    let (mut kind_variants, mut kind_arms) = (vec![], vec![]);
+    let mut message_entries = vec![];
+    let kind_name = format_ident!("{}Kind", enum_name.unraw());
+    let (mut severity_arms, mut has_severity) = (vec![], false);
+    let (mut from_impls, mut from_sources) = (vec![], vec![]);
+    let mut discriminant = quote!(0);
+    for (index, variant) in variants.iter().enumerate() {
+        let ErrorAttr {
+            span,
+            literal,
+            positional_args,
+            named_args,
+            severity,
+            message,
+            strict,
+        } = ErrorAttr::from_variant(variant, &options)?;
+
+        let mut error_message = literal.value();
+        if let Some(prefix) = applicable_prefix(&options, variant)? {
+            error_message.insert_str(0, &prefix);
+        }
+        if let Some(suffix) = source_suffix(&options, variant, &error_message)? {
+            error_message.push_str(&suffix);
+        }
+        let interpolator =
+            Interpolate::parse_with_delimiters(&error_message, variant, options.delimiters)
+                .with_specs(options.specs.clone())
+                .with_crate(options.krate)
+                .with_dedup_args(options.dedup_args)
+                .with_propagate_alternate(options.propagate_alternate)
+                .with_args(positional_args, named_args)
+                .with_aliases(source_alias(variant)?)
+                .with_binding_names(binding_names(variant)?);
+        // Every problem of the format string is reported at once.
+        let mut errors = interpolator
+            .errors
+            .iter()
+            .map(|error| Error::new(literal.span(), error));
+        if let Some(mut first) = errors.next() {
+            errors.for_each(|error| first.combine(error));
+            return Err(first);
+        }
+        validate_positionals(&interpolator, span)?;
+        if strict {
+            validate_strict(&interpolator)?;
+        }
+        interpolators.push(interpolator);
+
+        let variant_name = &variant.ident;
+        let cfg_attrs = cfg_attrs(variant).collect::<Vec<_>>();
+        let template = match &message {
+            Some(path) => quote!(#path),
+            None => quote!(#literal),
+        };
+        template_arms.push(quote! {
+            #(#cfg_attrs)*
+            Self::#variant_name { .. } => #template,
+        });
+
+        let name = variant_name.unraw().to_string();
+        message_entries.push(quote! {
+            #(#cfg_attrs)*
+            (#name, #template)
+        });
+        name_arms.push(quote! {
+            #(#cfg_attrs)*
+            Self::#variant_name { .. } => #name,
+        });
+
+        has_severity |= severity.is_some();
+        let severity = Ident::new(
+            &format!("{:?}", severity.unwrap_or_default()),
+            Span::call_site(),
+        );
+        severity_arms.push(quote! {
+            #(#cfg_attrs)*
+            Self::#variant_name { .. } => ::simple_error::Severity::#severity,
+        });
+
+        index_arms.push(quote! {
+            #(#cfg_attrs)*
+            Self::#variant_name { .. } => #index,
+        });
+
+        kind_variants.push(quote! {
+            #(#cfg_attrs)*
+            #variant_name,
+        });
+        kind_arms.push(quote! {
+            #(#cfg_attrs)*
+            Self::#variant_name { .. } => #kind_name::#variant_name,
+        });
+
+        // Variants without an explicit discriminant follow on from the previous one.
+        if let Some((_, expr)) = &variant.discriminant {
+            discriminant = quote!(#expr);
+        }
+        discriminant_arms.push(quote! {
+            #(#cfg_attrs)*
+            Self::#variant_name { .. } => #discriminant,
+        });
+        discriminant = quote!((#discriminant) + 1);
+
+        for ty in source_types(variant) {
+            let source = ty.to_token_stream().to_string();
+            if let Some((_, other)) = from_sources.iter().find(|(other, _)| *other == source) {
                 return Err(Error::new(
-                    attr.span(),
-                    r#"String literal expected in #[error(...)] attribute e.g. #[error("error message")]"#,
+                    ty.span(),
+                    format!("`{other}` already has #[from] for this type, making `From` ambiguous"),
                 ));
+            }
+            from_sources.push((source, variant_name));
+        }
+        from_impls.extend(impl_from(input, variant)?);
+    }
+
+    let vis = options.vis.clone().unwrap_or_else(|| parse_quote!(pub));
+    let mut methods = vec![];
+    if options.templates {
+        methods.push(quote! {
+            /// Returns the format string the variant's message is interpolated from.
+            #[must_use]
+            #vis fn message_template(&self) -> &'static str {
+                match self {
+                    #(#template_arms)*
+                }
+            }
+        });
+    }
+
+    if options.message_table {
+        methods.push(quote! {
+            /// The name of each variant, in declaration order, along with its format string.
+            #vis const MESSAGES: &'static [(&'static str, &'static str)] = &[
+                #(#message_entries),*
+            ];
+        });
+    }
+
+    if has_severity {
+        methods.push(quote! {
+            /// Returns the severity of the variant, as set by `#[error("...", severity = "...")]`.
+            #[must_use]
+            #vis fn severity(&self) -> ::simple_error::Severity {
+                match self {
+                    #(#severity_arms)*
+                }
+            }
+        });
+    }
+
+    if options.chain {
+        methods.push(quote! {
+            /// Returns an iterator over the error and each of its successive sources.
+            #vis fn chain(&self) -> ::simple_error::Chain<'_>
+            where
+                Self: ::std::error::Error + 'static,
+            {
+                ::simple_error::Chain::new(self)
+            }
+        });
+    }
+
+    if options.index {
+        methods.push(quote! {
+            /// Returns the position of the variant in the declaration of the enum, starting from 0.
+            #[must_use]
+            #vis fn variant_index(&self) -> usize {
+                match self {
+                    #(#index_arms)*
+                }
+            }
+        });
+    }
+
+    if options.kind {
+        methods.push(quote! {
+            /// Returns the kind of the variant, without its fields.
+            #[must_use]
+            #vis const fn kind(&self) -> #kind_name {
+                match self {
+                    #(#kind_arms)*
+                }
+            }
+
+            /// Returns whether the error is a variant of the given kind.
+            #[must_use]
+            #vis const fn matches(&self, kind: #kind_name) -> bool {
+                self.kind() as isize == kind as isize
+            }
+        });
+    }
+
+    if options.into_boxed {
+        methods.push(quote! {
+            /// Returns the error as a boxed trait object, which can be downcast back to the enum.
+            #[must_use]
+            #vis fn into_boxed(
+                self,
+            ) -> ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>
+            where
+                Self: ::std::error::Error + ::std::marker::Send + ::std::marker::Sync + 'static,
+            {
+                ::std::boxed::Box::new(self)
+            }
+        });
+    }
+
+    if options.json {
+        methods.push(quote! {
+            /// Returns the variant name and message as a JSON object,
+            /// e.g. `{"error":"NotFound","message":"Not found"}`.
+            #[must_use]
+            #vis fn to_json(&self) -> ::std::string::String
+            where
+                Self: ::std::fmt::Display,
+            {
+                fn escape(json: &mut ::std::string::String, text: &str) {
+                    for c in text.chars() {
+                        match c {
+                            '"' => json.push_str("\\\""),
+                            '\\' => json.push_str("\\\\"),
+                            '\n' => json.push_str("\\n"),
+                            '\r' => json.push_str("\\r"),
+                            '\t' => json.push_str("\\t"),
+                            c if c.is_control() => {
+                                json.push_str(&::std::format!("\\u{:04x}", c as u32));
+                            }
+                            c => json.push(c),
+                        }
+                    }
+                }
+
+                let name = match self {
+                    #(#name_arms)*
+                };
+
+                let mut json = ::std::string::String::from("{\"error\":\"");
+                escape(&mut json, name);
+                json.push_str("\",\"message\":\"");
+                escape(&mut json, &::std::string::ToString::to_string(self));
+                json.push_str("\"}");
+                json
+            }
+        });
+    }
+
+    if options.summary {
+        methods.push(quote! {
+            /// Returns the first line of the message, or the whole message if it's a single line.
+            #[must_use]
+            #vis fn summary(&self) -> ::std::string::String
+            where
+                Self: ::std::fmt::Display,
+            {
+                let mut message = ::std::string::ToString::to_string(self);
+                if let ::std::option::Option::Some(end) = message.find('\n') {
+                    message.truncate(end);
+                }
+                message
+            }
+        });
+    }
+
+    if options.format_into {
+        methods.push(quote! {
+            /// Appends the message to the buffer, e.g. to reuse it across errors.
+            #vis fn format_into(&self, buf: &mut ::std::string::String)
+            where
+                Self: ::std::fmt::Display,
+            {
+                ::std::fmt::Write::write_fmt(buf, ::std::format_args!("{}", self))
+                    .expect("a Display implementation returned an error unexpectedly");
+            }
+        });
+    }
+
+    let mut bounds = display_bounds(&input.generics, &interpolators);
+    let falls_back_to_debug = options.debug_fallback
+        && variants
+            .iter()
+            .any(|v| !v.attrs.iter().any(|attr| attr.path().is_ident("error")));
+    if falls_back_to_debug && input.generics.type_params().next().is_some() {
+        bounds.push(parse_quote!(Self: #krate::fmt::Debug));
+    }
+    let mut display_body = quote! {
+        match self {
+            #(#interpolators)*
+        }
+    };
+    if let Some(message) = catch_all_message(&options, &interpolators)? {
+        // The message is rendered before writing it, so that a failing field doesn't leave
+        // part of it written, and a failing writer isn't written to again.
+        display_body = quote! {
+            struct Render<'a>(&'a dyn Fn(&mut ::std::fmt::Formatter) -> ::std::fmt::Result);
+
+            impl ::std::fmt::Display for Render<'_> {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    (self.0)(f)
+                }
+            }
+
+            let render = |f: &mut ::std::fmt::Formatter| #display_body;
+            let mut rendered = ::std::string::String::new();
+            let result = if f.alternate() {
+                ::std::fmt::Write::write_fmt(&mut rendered, ::std::format_args!("{:#}", Render(&render)))
+            } else {
+                ::std::fmt::Write::write_fmt(&mut rendered, ::std::format_args!("{}", Render(&render)))
             };
+            match result {
+                ::std::result::Result::Ok(()) => f.write_str(&rendered),
+                ::std::result::Result::Err(_) => ::std::write!(f, #message),
+            }
+        };
+    }
+    if options.render {
+        methods.push(quote! {
+            /// Writes the message of the variant, as interpolated from its `#[error(...)]` attribute.
+            #vis fn render(&self, f: &mut #krate::fmt::Formatter) -> #krate::fmt::Result
+            where
+                #(#bounds,)*
+            {
+                #display_body
+            }
+        });
+
+        display_body = match &options.render_with {
+            Some(render_with) => quote!(#render_with(self, f)),
+            None => quote!(self.render(f)),
+        };
+    }
 
-            let error_message = literal.value();
-            let interpolator  = Interpolate::parse(&error_message, variant);
-            Ok(quote!(#interpolator))
+    if options.padded {
+        // Formatting without a width or precision writes the message as-is, so that
+        // the message only has to be rendered once before padding it.
+        display_body = quote! {
+            if f.width().is_none() && f.precision().is_none() {
+                return #display_body;
+            }
+
+            let message = if f.alternate() {
+                ::std::format!("{:#}", self)
+            } else {
+                ::std::format!("{}", self)
+            };
+            f.pad(&message)
+        };
+    }
 
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+    let has_discriminants = variants.iter().any(|v| v.discriminant.is_some());
+    if let Some(repr) = repr.filter(|_| has_discriminants) {
+        methods.push(quote! {
+            /// Returns the discriminant of the variant, as declared on the enum.
+            #[must_use]
+            #vis fn discriminant(&self) -> #repr {
+                match self {
+                    #(#discriminant_arms)*
+                }
+            }
+        });
+    }
 
     let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
-    let impls = quote! {
-        impl #impl_generics std::fmt::Display for #enum_name #ty_generics #where_clause {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let inherent_impl = (!methods.is_empty()).then(|| {
+        quote! {
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                #(#methods)*
+            }
+        }
+    });
+
+    // Spanned to the enum, so that a field which isn't thread-safe is reported there.
+    // Boxing needs the enum to be thread-safe, which is then asserted the same way.
+    let send_sync_assertion = (options.assert_send_sync || options.into_boxed).then(|| {
+        quote_spanned! {enum_name.span()=>
+            const _: () = {
+                fn assert_send_sync<T: ?Sized + #krate::marker::Send + #krate::marker::Sync>() {}
+
+                #[allow(dead_code)]
+                fn assert_enum_send_sync #impl_generics () #where_clause {
+                    assert_send_sync::<#enum_name #ty_generics>();
+                }
+            };
+        }
+    });
+
+    let display_generics = with_bounds(&input.generics, &bounds);
+    let display_where_clause = &display_generics.where_clause;
+    let mut sources = variants
+        .iter()
+        .map(source_field)
+        .filter_map(Result::transpose)
+        .map(|source| source.map(|(_, field)| field))
+        .collect::<syn::Result<Vec<_>>>()?;
+    sources.extend(
+        variants
+            .iter()
+            .filter_map(transparent_field)
+            .map(|(_, field)| field),
+    );
+    let source_method = impl_source(&variants.iter().collect::<Vec<_>>(), krate)?;
+    let error_bounds = error_bounds(&input.generics, krate, &sources);
+    let error_generics = with_bounds(&input.generics, &error_bounds);
+    let error_where_clause = &error_generics.where_clause;
+    let inline = options.inline.then(|| quote!(#[inline]));
+    let enum_vis = &input.vis;
+    let kind_enum = options.kind.then(|| {
+        let doc = format!("The kinds of [`{enum_name}`], i.e. its variants without their fields.");
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #enum_vis enum #kind_name {
+                #(#kind_variants)*
+            }
+        }
+    });
+    // Some older code still calls the deprecated `Error::description()`.
+    let description_method = cfg!(feature = "legacy").then(|| {
+        quote! {
+            #[allow(deprecated)]
+            fn description(&self) -> &str {
                 match self {
-                    #(#match_arms)*
+                    #(#template_arms)*
+                }
+            }
+        }
+    });
+    let error_trait_impl = options.error_trait.as_ref().map(|error_trait| {
+        quote! {
+            impl #impl_generics #error_trait for #enum_name #ty_generics #error_where_clause {
+                fn message(&self) -> ::std::string::String {
+                    ::std::string::ToString::to_string(self)
                 }
             }
         }
+    });
+    let impls = quote! {
+        impl #impl_generics #krate::fmt::Display for #enum_name #ty_generics #display_where_clause {
+            #inline
+            fn fmt(&self, f: &mut #krate::fmt::Formatter) -> #krate::fmt::Result {
+                #display_body
+            }
+        }
 
-        impl #impl_generics std::error::Error for #enum_name #ty_generics #where_clause {}
+        impl #impl_generics #krate::error::Error for #enum_name #ty_generics #error_where_clause {
+            #description_method
+
+            #source_method
+        }
+
+        #error_trait_impl
+
+        #inherent_impl
+
+        #kind_enum
+
+        #(#from_impls)*
+
+        #send_sync_assertion
     };
 
     Ok(impls)
 }
+
+/// Let `{source}` refer to the source of the variant, when it isn't a field named `source`.
+fn source_alias(variant: &Variant) -> syn::Result<Vec<(String, Member)>> {
+    let has_source_field = variant
+        .fields
+        .iter()
+        .any(|field| field.ident.as_ref().is_some_and(|ident| ident == "source"));
+
+    Ok(match source_field(variant)? {
+        Some((member, _)) if !has_source_field => vec![("source".to_string(), member)],
+        _ => vec![],
+    })
+}
+
+/// The ` (caused by: {source})` suffix appended by `show_source`, if the variant has a
+/// source its message doesn't already display.
+fn source_suffix(
+    options: &Options,
+    variant: &Variant,
+    error_message: &str,
+) -> syn::Result<Option<String>> {
+    if !options.show_source || transparent_field(variant).is_some() {
+        return Ok(None);
+    }
+
+    if source_field(variant)?.is_none() {
+        return Ok(None);
+    }
+
+    let interpolator =
+        Interpolate::parse_with_delimiters(error_message, variant, options.delimiters);
+    if interpolator.identifiers.contains("source") {
+        return Ok(None);
+    }
+
+    let Delimiters { open, close } = options.delimiters;
+    Ok(Some(format!(" (caused by: {open}source{close})")))
+}
+
+/// The message of the `catch_all` variant, which must be a unit variant displaying a
+/// message without placeholders, so that writing it can't fail the same way.
+fn catch_all_message(
+    options: &Options,
+    interpolators: &[Interpolate],
+) -> syn::Result<Option<String>> {
+    let Some(name) = &options.catch_all else {
+        return Ok(None);
+    };
+
+    let interpolator = interpolators
+        .iter()
+        .find(|interpolator| interpolator.variant.ident.unraw() == name.value())
+        .ok_or_else(|| Error::new(name.span(), format!("No variant named `{}`", name.value())))?;
+    if !matches!(interpolator.variant.fields, Fields::Unit) {
+        return Err(Error::new(
+            name.span(),
+            "The catch_all variant must be a unit variant",
+        ));
+    }
+
+    if !interpolator.placeholders.is_empty()
+        || !interpolator.positional_args.is_empty()
+        || !interpolator.named_args.is_empty()
+    {
+        return Err(Error::new(
+            name.span(),
+            "The message of the catch_all variant can't have placeholders",
+        ));
+    }
+
+    Ok(Some(interpolator.rewritten_text.clone()))
+}
+
+/// The `prefix` option, if every field it refers to is a field of the variant.
+fn applicable_prefix(options: &Options, variant: &Variant) -> syn::Result<Option<String>> {
+    let Some(prefix) = &options.prefix else {
+        return Ok(None);
+    };
+
+    let interpolator =
+        Interpolate::parse_with_delimiters(prefix.value(), variant, options.delimiters);
+    if let Some(error) = interpolator.errors.first() {
+        return Err(Error::new(prefix.span(), error));
+    }
+
+    // Positional values would shift the ones of the variant's own format string.
+    if interpolator.positional_indices().next().is_some() {
+        return Err(Error::new(
+            prefix.span(),
+            "The prefix can only refer to fields by name e.g. prefix = \"[{request_id}] \"",
+        ));
+    }
+
+    let applies = interpolator
+        .placeholders
+        .iter()
+        .all(|placeholder| placeholder.is_path() || interpolator.field(placeholder).is_some());
+    Ok(applies.then(|| prefix.value()))
+}
+
+/// Find the primitive integer type set on the enum by `#[repr(...)]`, if any.
+fn primitive_repr(attrs: &[Attribute]) -> syn::Result<Option<Ident>> {
+    const PRIMITIVES: &[&str] = &[
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    ];
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        let repr = metas.iter().find_map(|meta| match meta {
+            Meta::Path(path) => path
+                .get_ident()
+                .filter(|ident| PRIMITIVES.iter().any(|primitive| ident == primitive)),
+            _ => None,
+        });
+
+        if let Some(repr) = repr {
+            return Ok(Some(repr.clone()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Ensure every field of a strict variant is used by its message, other than its source
+/// and `#[from]` field.
+fn validate_strict(interpolator: &Interpolate) -> syn::Result<()> {
+    let variant = interpolator.variant;
+    let bound = interpolator.bound_fields();
+    let source = source_field(variant)?.map(|(member, _)| member);
+    for (index, field) in variant.fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(index.into()),
+        };
+
+        let is_from = field.attrs.iter().any(|attr| attr.path().is_ident("from"));
+        if bound.contains(&member) || source.as_ref() == Some(&member) || is_from {
+            continue;
+        }
+
+        let name = match &member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        };
+        return Err(Error::new(
+            field.span(),
+            format!(
+                "Field `{name}` of the strict variant `{}` isn't used by its message",
+                variant.ident
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Ensure every positional value in the format string refers to an existing tuple
+/// field, or to an explicit argument when those are given, each of which must be used.
+fn validate_positionals(interpolator: &Interpolate, span: Span) -> syn::Result<()> {
+    let variant = interpolator.variant;
+    let args = interpolator.positional_args.len();
+    if args > 0 {
+        // Like `format!`, every positional argument must be used.
+        let indices = interpolator.positional_indices().collect::<Vec<_>>();
+        let referenced = match indices.last() {
+            Some(last) if *last >= args => last + 1,
+            _ => indices.len(),
+        };
+
+        if referenced != args {
+            return Err(Error::new(
+                span,
+                format!(
+                    "The format string references {referenced} positional arguments, but {args} were provided"
+                ),
+            ));
+        }
+
+        return Ok(());
+    }
+
+    for index in interpolator.positional_indices() {
+        let is_field = match &variant.fields {
+            Fields::Unnamed(fields) => index < fields.unnamed.len(),
+            // A named field can legitimately be called `__0`, in which case it is referenced by name.
+            Fields::Named(fields) => fields.named.iter().any(|field| {
+                field
+                    .ident
+                    .as_ref()
+                    .is_some_and(|i| *i == format!("__{index}"))
+            }),
+            Fields::Unit => false,
+        };
+
+        if !is_field {
+            return Err(Error::new(
+                span,
+                format!(
+                    "Positional value {{{index}}} does not match any field of `{}`",
+                    variant.ident
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use crate::impl_display_error;
+
+    #[test]
+    fn test_match_has_no_wildcard_arm() {
+        let input = parse_quote! {
+            #[simple_error(templates)]
+            enum SomeError {
+                #[error("Unit error")]
+                Unit,
+
+                #[error("Unnamed error: {1}")]
+                Unnamed(i32, i32),
+
+                #[error("Named error")]
+                Named { message: String },
+            }
+        };
+
+        // `description()` matches on every variant as well.
+        let arms = 2 + usize::from(cfg!(feature = "legacy"));
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(!tokens.contains("_ =>"));
+        assert_eq!(tokens.matches("Self :: Unit").count(), arms);
+        assert_eq!(tokens.matches("Self :: Unnamed").count(), arms);
+        assert_eq!(tokens.matches("Self :: Named").count(), arms);
+    }
+
+    #[test]
+    fn test_no_std_rejects_allocating_options() {
+        let cases = [
+            (
+                parse_quote! {
+                    #[simple_error(no_std, padded)]
+                    enum SomeError {
+                        #[error("Unit error")]
+                        Unit,
+                    }
+                },
+                "#[simple_error(padded)] allocates, so it can't be used with no_std",
+            ),
+            (
+                parse_quote! {
+                    #[simple_error(no_std, json)]
+                    enum SomeError {
+                        #[error("Unit error")]
+                        Unit,
+                    }
+                },
+                "#[simple_error(json)] allocates, so it can't be used with no_std",
+            ),
+            (
+                parse_quote! {
+                    #[simple_error(catch_all = "Unit")]
+                    #[simple_error(no_std)]
+                    enum SomeError {
+                        #[error("Unit error")]
+                        Unit,
+                    }
+                },
+                "#[simple_error(catch_all)] allocates, so it can't be used with no_std",
+            ),
+        ];
+
+        for (input, message) in cases {
+            let error = impl_display_error(&input).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_accessors_are_must_use() {
+        let input = parse_quote! {
+            #[simple_error(templates)]
+            #[repr(u8)]
+            enum SomeError {
+                #[error("Unit error")]
+                Unit = 1,
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains("# [must_use] pub fn message_template"));
+        assert!(tokens.contains("# [must_use] pub fn discriminant"));
+    }
+
+    #[test]
+    fn test_inline() {
+        let input = parse_quote! {
+            #[simple_error(inline)]
+            enum SomeError {
+                #[error("Unit error")]
+                Unit,
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains("# [inline] fn fmt"));
+
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("Unit error")]
+                Unit,
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(!tokens.contains("# [inline]"));
+    }
+
+    #[test]
+    fn test_stacked_error_attributes() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("Request failed:")]
+                #[error("{0}")]
+                Request(u16),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Only one #[error(...)] attribute is allowed per variant, \
+             use #[simple_error(concat_errors)] to join them"
+        );
+    }
+
+    #[test]
+    fn test_method_visibility() {
+        let input = parse_quote! {
+            #[simple_error(templates, index, vis = "pub(crate)")]
+            pub enum SomeError {
+                #[error("Unit error")]
+                Unit,
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains("# [must_use] pub (crate) fn message_template"));
+        assert!(tokens.contains("# [must_use] pub (crate) fn variant_index"));
+
+        let input = parse_quote! {
+            #[simple_error(templates, vis = "")]
+            pub enum SomeError {
+                #[error("Unit error")]
+                Unit,
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains("# [must_use] fn message_template"));
+    }
+
+    #[test]
+    fn test_duplicate_from_source() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("First: {0}")]
+                First(#[from] std::io::Error),
+
+                #[error("Second: {0}")]
+                Second(#[from] std::io::Error),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`First` already has #[from] for this type, making `From` ambiguous"
+        );
+
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{0}")]
+                Owned(#[from] String),
+
+                #[error("{0}")]
+                Borrowed(#[from] &str),
+            }
+        };
+
+        assert!(impl_display_error(&input).is_err());
+    }
+
+    #[test]
+    fn test_field_formatted_both_ways_needs_both_bounds() {
+        let input = parse_quote! {
+            enum SomeError<T> {
+                #[error("{0} ({0:?})")]
+                Invalid(T),
+
+                #[error("{0:?}")]
+                Other(T),
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.starts_with(
+            "impl < T > :: std :: fmt :: Display for SomeError < T > \
+             where T : :: core :: fmt :: Display , T : :: core :: fmt :: Debug {"
+        ));
+    }
+
+    #[test]
+    fn test_error_bounds_only_apply_to_sources() {
+        let input = parse_quote! {
+            enum SomeError<S, T, F> {
+                #[error("source error")]
+                Source(#[source] S),
+
+                #[error("shown: {0}")]
+                Shown(T),
+
+                #[error("converted")]
+                Converted(#[from(no_source)] F),
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        let error_impl = &tokens[tokens.find(":: std :: error :: Error for").unwrap()..];
+        let where_clause = &error_impl[..error_impl.find('{').unwrap()];
+        assert_eq!(
+            where_clause,
+            ":: std :: error :: Error for SomeError < S , T , F > where Self : :: std :: fmt :: Debug \
+             + :: std :: fmt :: Display , S : :: std :: error :: Error + 'static "
+        );
+    }
+
+    #[test]
+    fn test_every_format_string_problem_is_reported() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{0:.x} } {")]
+                Broken(u8),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        let messages = error.into_iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].starts_with("Invalid precision in the format spec `.x` at offset 0"));
+        assert!(messages[1].starts_with("Unmatched `}` at offset 7"));
+        assert!(messages[2].starts_with("Unterminated placeholder at offset 9"));
+    }
+
+    #[test]
+    fn test_binding_names() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{0}:{1} {2}")]
+                #[name(0 = "path", 1 = "line")]
+                Parse(String, u32, u8),
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains(
+            r#"Self :: Parse (path , line , __self_2 , ..) => :: std :: write ! (f , "{__0}:{__1} {__2}" , __0 = path , __1 = line , __2 = __self_2)"#
+        ));
+
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{0}")]
+                #[name(0 = "path", 1 = "path")]
+                Parse(String, u32),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(error.to_string(), "`path` already names another field");
+
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{0}")]
+                #[name(2 = "line")]
+                Parse(String, u32),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(error.to_string(), "`Parse` has no field 2");
+    }
+
+    #[test]
+    fn test_missing_included_template() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error(include = "tests/messages/missing.txt")]
+                Missing,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert!(error.to_string().starts_with("Failed to read `"));
+        assert!(error.to_string().contains("missing.txt"));
+    }
+
+    #[test]
+    fn test_catch_all_must_be_a_unit_variant_without_placeholders() {
+        let input = parse_quote! {
+            #[simple_error(catch_all = "Missing")]
+            enum SomeError {
+                #[error("Unknown error")]
+                Unknown,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(error.to_string(), "No variant named `Missing`");
+
+        let input = parse_quote! {
+            #[simple_error(catch_all = "Unknown")]
+            enum SomeError {
+                #[error("Unknown error: {0}")]
+                Unknown(u8),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The catch_all variant must be a unit variant"
+        );
+
+        let input = parse_quote! {
+            #[simple_error(catch_all = "Unknown")]
+            enum SomeError {
+                #[error("Unknown error: {}", self.code())]
+                Unknown,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The message of the catch_all variant can't have placeholders"
+        );
+    }
+
+    #[test]
+    fn test_output_is_stable() {
+        let input = parse_quote! {
+            enum SomeError<T> {
+                #[error("{10} {2:?} {2} {Self::PREFIX} {0:path}")]
+                Unnamed(std::path::PathBuf, u8, T, u8, u8, u8, u8, u8, u8, u8, T),
+
+                #[error("{b} {a:x} {label}", label = "c")]
+                Named { a: T, b: T },
+            }
+        };
+
+        // The `legacy` feature is enabled for every crate of the build once any depends on it.
+        let description = if cfg!(feature = "legacy") {
+            concat!(
+                "# [allow (deprecated)] fn description (& self) -> & str { match self { ",
+                r#"Self :: Unnamed { .. } => "{10} {2:?} {2} {Self::PREFIX} {0:path}" , "#,
+                r#"Self :: Named { .. } => "{b} {a:x} {label}" , } } "#,
+            )
+        } else {
+            ""
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert_eq!(tokens, impl_display_error(&input).unwrap().to_string());
+        let expected = concat!(
+            "impl < T > :: std :: fmt :: Display for SomeError < T > ",
+            "where T : :: core :: fmt :: Display , T : :: core :: fmt :: Debug , ",
+            "T : :: core :: fmt :: LowerHex { ",
+            "fn fmt (& self , f : & mut :: std :: fmt :: Formatter) -> :: std :: fmt :: Result { ",
+            "match self { ",
+            "Self :: Unnamed (__self_0 , _ , __self_2 , _ , _ , _ , _ , _ , _ , _ , __self_10 , ..) => ",
+            r#":: std :: write ! (f , "{__10} {__2:?} {__2} {__Self__PREFIX} {__path_0}" , "#,
+            "__10 = __self_10 , __2 = __self_2 , __Self__PREFIX = Self :: PREFIX , ",
+            "__path_0 = __self_0 . display ()) , ",
+            "Self :: Named { a , b , .. } => ",
+            r#":: std :: write ! (f , "{b} {a:x} {label}" , label = ("c")) , "#,
+            "} } } ",
+            "impl < T > :: std :: error :: Error for SomeError < T > ",
+            "where Self : :: std :: fmt :: Debug + :: std :: fmt :: Display { ",
+        );
+        assert_eq!(tokens, format!("{expected}{description}}}"));
+    }
+
+    #[test]
+    fn test_unterminated_placeholder() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("ends with {")]
+                Unit,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unterminated placeholder at offset 10"));
+    }
+
+    #[test]
+    fn test_named_bindings_keep_declaration_order() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{zeta} {alpha:?} {zeta}", code = mid.len())]
+                Named { zeta: u8, unused: u8, mid: String, alpha: u8, last: u8 },
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains("Self :: Named { zeta , mid , alpha , .. } =>"));
+    }
+
+    #[test]
+    fn test_named_debug_placeholder_binds_its_field_only() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{a:?}")]
+                Named { a: String, b: u8 },
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains(concat!(
+            "Self :: Named { a , .. } => ",
+            r#":: std :: write ! (f , "{a:?}" ,)"#,
+        )));
+        assert!(!tokens.contains("b ,"));
+    }
+
+    #[test]
+    fn test_transparent_requires_one_field() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error(transparent)]
+                Pair(std::io::Error, u8),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "#[error(transparent)] requires the variant to have exactly one field"
+        );
+    }
+
+    #[test]
+    fn test_macro_call_format_string() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error(format!("{} v{}", NAME, VERSION))]
+                Version,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"`format!` can't be expanded in #[error(...)] attributes, use a string literal e.g. #[error("error message")], a constant with #[error(ref = MESSAGE)], or a file with #[error(include = "message.txt")]"#
+        );
+
+        let input = parse_quote! {
+            enum SomeError {
+                #[error = concat!("a", "b")]
+                Concat,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert!(error.to_string().starts_with("`concat!` can't be expanded"));
+    }
+
+    #[test]
+    fn test_transparent_stands_alone() {
+        let cases: [(syn::DeriveInput, &str); 5] = [
+            (
+                parse_quote! {
+                    enum SomeError {
+                        #[error(transparent, "Io error: {0}")]
+                        Io(std::io::Error),
+                    }
+                },
+                "#[error(transparent)] can't be combined with a format string",
+            ),
+            (
+                parse_quote! {
+                    enum SomeError {
+                        #[error(transparent, self.0)]
+                        Io(std::io::Error),
+                    }
+                },
+                "#[error(transparent)] can't be combined with arguments",
+            ),
+            (
+                parse_quote! {
+                    enum SomeError {
+                        #[error(debug, "{0:?}")]
+                        Io(std::io::Error),
+                    }
+                },
+                "#[error(debug)] can't be combined with a format string",
+            ),
+            (
+                parse_quote! {
+                    enum SomeError {
+                        #[error("Io error")]
+                        #[error(transparent)]
+                        Io(std::io::Error),
+                    }
+                },
+                "#[error(transparent)] can't be combined with another #[error(...)] attribute",
+            ),
+            (
+                parse_quote! {
+                    enum SomeError {
+                        #[error(transparent)]
+                        Io { source: std::io::Error, path: String },
+                    }
+                },
+                "#[error(transparent)] requires the variant to have exactly one field",
+            ),
+        ];
+
+        for (input, message) in cases {
+            let error = impl_display_error(&input).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_trailing_comma_in_args() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{} ({})", self.code(), 10 * 2,)]
+                Positional,
+
+                #[error("{label}", label = "named",)]
+                Named,
+
+                #[error("No arguments",)]
+                Empty,
+
+                #[error("Warned", severity = "warn",)]
+                Warned,
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains(concat!(
+            r#"Self :: Positional => :: std :: write ! (f , "{__0} ({__1})" , "#,
+            "__0 = (self . code ()) , __1 = (10 * 2)) ,",
+        )));
+        assert!(tokens.contains(
+            r#"Self :: Named => :: std :: write ! (f , "{label}" , label = ("named")) ,"#
+        ));
+        assert!(tokens.contains(r#"Self :: Empty => :: std :: write ! (f , "No arguments" ,) ,"#));
+        assert!(tokens.contains(r#"Self :: Warned => :: std :: write ! (f , "Warned" ,) ,"#));
+    }
+
+    #[test]
+    fn test_args_bind_only_the_fields_they_use() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{} {} {}", self.code(), Self::label(), value)]
+                Named { code: u8, label: u8, value: u8 },
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains("Self :: Named { value , .. } => "));
+    }
+
+    #[test]
+    fn test_strict_variant() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{path}", strict)]
+                Missing { path: String, code: u8 },
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Field `code` of the strict variant `Missing` isn't used by its message"
+        );
+
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{0} at {1}", strict)]
+                Unnamed(u8, u8, u8),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Field `2` of the strict variant `Unnamed` isn't used by its message"
+        );
+
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{path} ({})", code.len(), strict)]
+                Named { path: String, code: String, source: std::io::Error },
+
+                #[error("{0}", strict, severity = "warn")]
+                Unnamed(u8, #[from] std::num::ParseIntError),
+
+                #[error("Unit error", strict)]
+                Unit,
+            }
+        };
+
+        assert!(impl_display_error(&input).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_precision() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("Ratio {0:.x}")]
+                Ratio(f64),
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid precision in the format spec `.x` at offset 6, expected a number e.g. `.3`, \
+             or a count argument e.g. `.prec$`"
+        );
+    }
+
+    #[test]
+    fn test_debug_requires_one_field() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error(debug)]
+                Unit,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "#[error(debug)] requires the variant to have exactly one field"
+        );
+    }
+
+    #[test]
+    fn test_unknown_severity() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("Unit error", severity = "fatal")]
+                Unit,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"Expected one of "trace", "debug", "info", "warn" or "error""#
+        );
+    }
+
+    #[test]
+    fn test_positional_args_mismatch() {
+        let error = |input| impl_display_error(&input).unwrap_err().to_string();
+
+        assert_eq!(
+            error(parse_quote! {
+                enum SomeError {
+                    #[error("{0} {1}", 1)]
+                    Unit,
+                }
+            }),
+            "The format string references 2 positional arguments, but 1 were provided"
+        );
+        assert_eq!(
+            error(parse_quote! {
+                enum SomeError {
+                    #[error("{} {0}", 1, 2, 3)]
+                    Unit,
+                }
+            }),
+            "The format string references 1 positional arguments, but 3 were provided"
+        );
+        assert_eq!(
+            error(parse_quote! {
+                enum SomeError {
+                    #[error("{1}", 1, 2)]
+                    Unit,
+                }
+            }),
+            "The format string references 1 positional arguments, but 2 were provided"
+        );
+    }
+
+    #[test]
+    fn test_rename_rules() {
+        use crate::case::RenameRule;
+
+        let rename = |rule, name| RenameRule::from_name(rule).unwrap().apply(name);
+        assert_eq!(rename("snake_case", "TooManyRequests"), "too_many_requests");
+        assert_eq!(rename("kebab-case", "HTTPError"), "http-error");
+        assert_eq!(
+            rename("SCREAMING_SNAKE_CASE", "Error404Page"),
+            "ERROR404_PAGE"
+        );
+        assert_eq!(rename("Title Case", "TooManyRequests"), "Too Many Requests");
+        assert_eq!(rename("lowercase", "TooMany"), "toomany");
+        assert_eq!(rename("UPPERCASE", "TooMany"), "TOOMANY");
+        assert_eq!(rename("snake_case", "A"), "a");
+        assert!(RenameRule::from_name("camelCase").is_none());
+    }
+
+    #[test]
+    fn test_debug_fallback_conflicts_with_by_name_and_missing_error() {
+        let input = parse_quote! {
+            #[simple_error(by_name, debug_fallback)]
+            enum SomeError {
+                Unit,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "#[simple_error(by_name)] and #[simple_error(debug_fallback)] can't be used together"
+        );
+
+        let input = parse_quote! {
+            enum SomeError {
+                Unit,
+            }
+        };
+
+        let error = impl_display_error(&input).unwrap_err();
+        assert_eq!(error.to_string(), "Missing #[error(...)] attribute");
+    }
+
+    #[test]
+    fn test_raw_identifier_fields() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("{r#type}: {r#type:?} {name}")]
+                Named { r#type: String, name: String, r#loop: u8 },
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(tokens.contains(concat!(
+            "Self :: Named { r#type , name , .. } => ",
+            r#":: std :: write ! (f , "{__r_type}: {__r_type:?} {name}" , __r_type = r#type)"#,
+        )));
+    }
+
+    #[test]
+    fn test_copy_positional_field() {
+        let input = parse_quote! {
+            enum SomeError {
+                #[error("Code {0:x}")]
+                Code(i32),
+            }
+        };
+
+        let tokens = impl_display_error(&input).unwrap().to_string();
+        assert!(!tokens.contains("self . __0"));
+        assert!(tokens.contains(concat!(
+            "Self :: Code (__self_0 , ..) => ",
+            r#":: std :: write ! (f , "Code {__0:x}" , __0 = __self_0)"#,
+        )));
+    }
+}
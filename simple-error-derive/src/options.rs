@@ -0,0 +1,289 @@
+use proc_macro2::Span;
+
+use crate::case::RenameRule;
+use simple_error::{Crate, Delimiters, PseudoSpec};
+use syn::{Attribute, Error, LitStr, Path, Token, Visibility};
+
+/// Options set on the enum through the `#[simple_error(...)]` attribute.
+#[derive(Default)]
+pub(crate) struct Options {
+    /// Generate `message_template()`, returning the raw format string of each variant.
+    pub templates: bool,
+
+    /// Generate `render()`, holding the formatting logic that `Display` delegates to.
+    pub render: bool,
+
+    /// Function called by `Display` instead of `render()`, allowing the output to be wrapped.
+    pub render_with: Option<Path>,
+
+    /// Characters enclosing the placeholders of every `#[error(...)]` format string.
+    pub delimiters: Delimiters,
+
+    /// The crate generated code refers to, `::core` for `no_std` and `::std` otherwise.
+    pub krate: Crate,
+
+    /// Render the message into a `String` first, so that `Display` honors the width,
+    /// fill, alignment and precision of the formatter.
+    pub padded: bool,
+
+    /// Generate `to_json()`, returning the variant name and message as a JSON object.
+    pub json: bool,
+
+    /// Generate `variant_index()`, returning the position of the variant in the enum.
+    pub index: bool,
+
+    /// Assert at compile time that the enum is `Send + Sync`.
+    pub assert_send_sync: bool,
+
+    /// Display the variants without an `#[error(...)]` attribute by their name.
+    pub by_name: bool,
+
+    /// Display the variants without an `#[error(...)]` attribute through `Debug`.
+    pub debug_fallback: bool,
+
+    /// Mark the generated `Display::fmt` as `#[inline]`.
+    pub inline: bool,
+
+    /// Join the format strings of stacked `#[error(...)]` attributes with a space.
+    pub concat_errors: bool,
+
+    /// A format string prepended to the message of the variants having every field it refers to.
+    pub prefix: Option<LitStr>,
+
+    /// The case the variants displayed by name are converted to.
+    pub rename_all: Option<RenameRule>,
+
+    /// Render the values formatted by several placeholders once, rather than by each of them.
+    pub dedup_args: bool,
+
+    /// Generate a fieldless `{Enum}Kind` enum, along with `kind()` and `matches()`.
+    pub kind: bool,
+
+    /// Generate `into_boxed()`, returning the error as a `Box<dyn Error + Send + Sync>`.
+    pub into_boxed: bool,
+
+    /// Pretty-print `Debug` placeholders when the error is displayed in alternate mode.
+    pub propagate_alternate: bool,
+
+    /// Append the source to the message of the variants having one.
+    pub show_source: bool,
+
+    /// Generate `MESSAGES`, listing the name and format string of each variant.
+    pub message_table: bool,
+
+    /// Generate `summary()`, returning the first line of the message.
+    pub summary: bool,
+
+    /// Generate `format_into()`, appending the message to an existing `String`.
+    pub format_into: bool,
+
+    /// Generate `chain()`, iterating over the error and its successive sources.
+    pub chain: bool,
+
+    /// The unit variant whose message is written instead, when displaying a variant fails.
+    pub catch_all: Option<LitStr>,
+
+    /// A custom error trait implemented along with `Error`, through its `message()` method.
+    pub error_trait: Option<Path>,
+
+    /// The visibility of the generated methods, `pub` by default.
+    pub vis: Option<Visibility>,
+
+    /// Custom specs, by name, along with the wrapper type displaying their values,
+    /// e.g. `hex = HexFmt` for `{0:hex}`.
+    pub specs: Vec<(String, Path)>,
+
+    /// The span of each option, by name, to report errors on the option itself.
+    spans: Vec<(String, Span)>,
+}
+
+impl Options {
+    /// Collect the options from every `#[simple_error(...)]` attribute on the enum.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Options::default();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("simple_error"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if let Some(ident) = meta.path.get_ident() {
+                    options.spans.push((ident.to_string(), ident.span()));
+                }
+
+                let flags = [
+                    ("templates", &mut options.templates),
+                    ("padded", &mut options.padded),
+                    ("json", &mut options.json),
+                    ("index", &mut options.index),
+                    ("assert_send_sync", &mut options.assert_send_sync),
+                    ("by_name", &mut options.by_name),
+                    ("chain", &mut options.chain),
+                    ("debug_fallback", &mut options.debug_fallback),
+                    ("inline", &mut options.inline),
+                    ("summary", &mut options.summary),
+                    ("format_into", &mut options.format_into),
+                    ("message_table", &mut options.message_table),
+                    ("show_source", &mut options.show_source),
+                    ("propagate_alternate", &mut options.propagate_alternate),
+                    ("into_boxed", &mut options.into_boxed),
+                    ("kind", &mut options.kind),
+                    ("dedup_args", &mut options.dedup_args),
+                    ("concat_errors", &mut options.concat_errors),
+                ];
+                if let Some((_, flag)) =
+                    flags.into_iter().find(|(name, _)| meta.path.is_ident(name))
+                {
+                    *flag = true;
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("render") {
+                    options.render = true;
+                    if meta.input.peek(Token![=]) {
+                        options.render_with = Some(meta.value()?.parse()?);
+                    }
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("spec") {
+                    return meta.parse_nested_meta(|spec| {
+                        let Some(name) = spec.path.get_ident().map(ToString::to_string) else {
+                            return Err(
+                                spec.error("Expected the name of the spec e.g. spec(hex = HexFmt)")
+                            );
+                        };
+
+                        if PseudoSpec::from_spec(&name).is_some() {
+                            return Err(spec.error(format!("`{name}` is a built-in spec")));
+                        }
+
+                        options.specs.push((name, spec.value()?.parse()?));
+                        Ok(())
+                    });
+                }
+
+                // A shorthand for `spec(num = ...)`, e.g. to group the digits of numbers.
+                if meta.path.is_ident("num") {
+                    options
+                        .specs
+                        .push(("num".to_string(), meta.value()?.parse()?));
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("prefix") {
+                    options.prefix = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("catch_all") {
+                    options.catch_all = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("error_trait") {
+                    options.error_trait = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("vis") {
+                    options.vis = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("rename_all") {
+                    let literal = meta.value()?.parse::<LitStr>()?;
+                    let rule = RenameRule::from_name(&literal.value()).ok_or_else(|| {
+                        let names = RenameRule::NAMES
+                            .iter()
+                            .map(|(name, _)| format!("\"{name}\""))
+                            .collect::<Vec<_>>();
+                        Error::new(
+                            literal.span(),
+                            format!("Expected one of {}", names.join(", ")),
+                        )
+                    })?;
+
+                    options.rename_all = Some(rule);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("no_std") {
+                    options.krate = Crate::Core;
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("delimiters") {
+                    let literal = meta.value()?.parse::<LitStr>()?;
+                    let value = literal.value();
+                    let mut chars = value.chars();
+                    let (Some(open), Some(close), None) =
+                        (chars.next(), chars.next(), chars.next())
+                    else {
+                        return Err(Error::new(
+                            literal.span(),
+                            r#"Expected the opening and closing characters e.g. delimiters = "%%""#,
+                        ));
+                    };
+
+                    options.delimiters = Delimiters { open, close };
+                    return Ok(());
+                }
+
+                Err(Error::new_spanned(
+                    &meta.path,
+                    "Unsupported option in #[simple_error(...)] attribute",
+                ))
+            })?;
+        }
+
+        if options.krate == Crate::Core {
+            let allocating = [
+                (options.padded, "padded"),
+                (options.json, "json"),
+                (options.summary, "summary"),
+                (options.format_into, "format_into"),
+                (options.dedup_args, "dedup_args"),
+                (options.catch_all.is_some(), "catch_all"),
+                (options.into_boxed, "into_boxed"),
+                (options.error_trait.is_some(), "error_trait"),
+            ];
+            if let Some((_, name)) = allocating.into_iter().find(|(enabled, _)| *enabled) {
+                return Err(Error::new(
+                    options.span(name),
+                    format!("#[simple_error({name})] allocates, so it can't be used with no_std"),
+                ));
+            }
+        }
+
+        if options.chain && options.krate == Crate::Core {
+            return Err(Error::new(
+                options.span("chain"),
+                "#[simple_error(chain)] relies on the simple-error crate, so it can't be used with no_std",
+            ));
+        }
+
+        if options.by_name && options.debug_fallback {
+            return Err(Error::new(
+                options.span("debug_fallback"),
+                "#[simple_error(by_name)] and #[simple_error(debug_fallback)] can't be used together",
+            ));
+        }
+
+        if options.rename_all.is_some() && !options.by_name {
+            return Err(Error::new(
+                options.span("rename_all"),
+                "#[simple_error(rename_all = \"...\")] only applies along with by_name",
+            ));
+        }
+
+        Ok(options)
+    }
+
+    /// The span of the option of the given name, or of the derive if it wasn't set.
+    fn span(&self, name: &str) -> Span {
+        self.spans
+            .iter()
+            .find(|(option, _)| option == name)
+            .map_or_else(Span::call_site, |(_, span)| *span)
+    }
+}
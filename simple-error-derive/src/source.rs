@@ -0,0 +1,144 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use simple_error::{cfg_attrs, Crate};
+
+use crate::{
+    attr::is_transparent,
+    from::{hides_source, implies_source},
+};
+use syn::{
+    spanned::Spanned, Error, Field, GenericArgument, Index, Member, PathArguments, Type, Variant,
+};
+
+/// Find the field returned by `Error::source()` for the variant: the field marked with
+/// `#[source]`, or else its `#[from]` field, or else the field named `source`, unless marked
+/// with `#[from(no_source)]`.
+pub(crate) fn source_field(variant: &Variant) -> syn::Result<Option<(Member, &Field)>> {
+    let mut members = vec![];
+    for (index, field) in variant.fields.iter().enumerate() {
+        if hides_source(field)? {
+            continue;
+        }
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        members.push((member, field));
+    }
+
+    let mut marked = members.iter().filter(|(_, field)| {
+        field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("source"))
+    });
+    if let Some(source) = marked.next() {
+        if let Some((_, field)) = marked.next() {
+            return Err(Error::new(
+                field.span(),
+                "#[source] can only be used on a single field of a variant",
+            ));
+        }
+
+        return Ok(Some(source.clone()));
+    }
+
+    if let Some(source) = members.iter().find(|(_, field)| implies_source(field)) {
+        return Ok(Some(source.clone()));
+    }
+
+    Ok(members
+        .into_iter()
+        .find(|(_, field)| field.ident.as_ref().is_some_and(|ident| ident == "source")))
+}
+
+/// The only field of an `#[error(transparent)]` variant, whose source is forwarded.
+pub(crate) fn transparent_field(variant: &Variant) -> Option<(Member, &Field)> {
+    if !variant.attrs.iter().any(is_transparent) || variant.fields.len() != 1 {
+        return None;
+    }
+
+    let field = variant.fields.iter().next()?;
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(0)),
+    };
+    Some((member, field))
+}
+
+/// Generate the `Error::source()` method, if any variant has a source.
+pub(crate) fn impl_source(variants: &[&Variant], krate: Crate) -> syn::Result<Option<TokenStream>> {
+    let mut has_source = false;
+    let mut arms = vec![];
+    for variant in variants {
+        let variant_name = &variant.ident;
+        let cfg_attrs = cfg_attrs(variant).collect::<Vec<_>>();
+        if let Some((member, field)) = transparent_field(variant) {
+            let source = if boxed_type(&field.ty).is_some() {
+                quote!(&**source)
+            } else {
+                quote!(source)
+            };
+
+            has_source = true;
+            arms.push(quote! {
+                #(#cfg_attrs)*
+                Self::#variant_name { #member: source, .. } => #krate::error::Error::source(#source),
+            });
+            continue;
+        }
+
+        let Some((member, field)) = source_field(variant)? else {
+            arms.push(quote! {
+                #(#cfg_attrs)*
+                Self::#variant_name { .. } => #krate::option::Option::None,
+            });
+            continue;
+        };
+
+        // A boxed source is borrowed through the box, rather than as the box itself, which
+        // doesn't implement `Error` for trait objects, and would hide the type of the others.
+        let source = if boxed_type(&field.ty).is_some() {
+            quote!(&**source)
+        } else {
+            quote!(source)
+        };
+
+        has_source = true;
+        arms.push(quote! {
+            #(#cfg_attrs)*
+            Self::#variant_name { #member: source, .. } => {
+                #krate::option::Option::Some(#source as &(dyn #krate::error::Error + 'static))
+            }
+        });
+    }
+
+    Ok(has_source.then(|| {
+        quote! {
+            fn source(&self) -> #krate::option::Option<&(dyn #krate::error::Error + 'static)> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    }))
+}
+
+/// The type held by the source, if it's (most likely) a `Box`, e.g. `dyn Error + Send`
+/// for `Box<dyn Error + Send>`.
+pub(crate) fn boxed_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last().filter(|s| s.ident == "Box")?;
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first() {
+        Some(GenericArgument::Type(ty)) if arguments.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
@@ -0,0 +1,405 @@
+use simple_error_derive::SimpleError;
+
+#[derive(Debug, SimpleError)]
+#[simple_error(templates)]
+enum TemplateError {
+    #[error("Unit error")]
+    Unit,
+
+    #[error("Unnamed error: {0:?}, {1}")]
+    Unnamed(i32, &'static str),
+
+    #[error("Named error: {message}")]
+    Named { message: String },
+}
+
+#[test]
+fn test_message_template() {
+    assert_eq!(TemplateError::Unit.message_template(), "Unit error");
+    assert_eq!(
+        TemplateError::Unnamed(1, "state").message_template(),
+        "Unnamed error: {0:?}, {1}"
+    );
+    assert_eq!(
+        TemplateError::Named {
+            message: "critical".to_string()
+        }
+        .message_template(),
+        "Named error: {message}"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[repr(i32)]
+enum CodeError {
+    #[error("Not found")]
+    NotFound = 404,
+
+    #[error("Gone")]
+    Gone,
+
+    #[error("Internal error: {0}")]
+    Internal(String) = 500,
+}
+
+#[test]
+fn test_discriminant() {
+    assert_eq!(CodeError::NotFound.discriminant(), 404);
+    assert_eq!(CodeError::Gone.discriminant(), 405);
+    assert_eq!(CodeError::Internal("boom".to_string()).discriminant(), 500);
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(render)]
+enum RenderError {
+    #[error("Render error: {0}")]
+    Unnamed(i32),
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(render = wrap_render)]
+enum WrappedRenderError {
+    #[error("Render error: {0}")]
+    Unnamed(i32),
+}
+
+fn wrap_render(error: &WrappedRenderError, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    f.write_str("[wrapped] ")?;
+    error.render(f)
+}
+
+#[test]
+fn test_render() {
+    struct Rendered<'a>(&'a RenderError);
+
+    impl std::fmt::Display for Rendered<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            self.0.render(f)
+        }
+    }
+
+    assert_eq!(RenderError::Unnamed(1).to_string(), "Render error: 1");
+    assert_eq!(
+        Rendered(&RenderError::Unnamed(1)).to_string(),
+        "Render error: 1"
+    );
+    assert_eq!(
+        WrappedRenderError::Unnamed(2).to_string(),
+        "[wrapped] Render error: 2"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(json)]
+enum JsonError {
+    #[error("Not found")]
+    NotFound,
+
+    #[error("Invalid {0}")]
+    Invalid(&'static str),
+
+    #[error("Reserved")]
+    r#Type,
+}
+
+#[test]
+fn test_to_json() {
+    assert_eq!(
+        JsonError::NotFound.to_json(),
+        r#"{"error":"NotFound","message":"Not found"}"#
+    );
+    assert_eq!(
+        JsonError::r#Type.to_json(),
+        r#"{"error":"Type","message":"Reserved"}"#
+    );
+}
+
+#[test]
+fn test_to_json_escapes_message() {
+    assert_eq!(
+        JsonError::Invalid(r#"path "C:\tmp""#).to_json(),
+        r#"{"error":"Invalid","message":"Invalid path \"C:\\tmp\""}"#
+    );
+    assert_eq!(
+        JsonError::Invalid("line\nbreak\u{1}").to_json(),
+        r#"{"error":"Invalid","message":"Invalid line\nbreak\u0001"}"#
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(json)]
+enum GenericJsonError<T> {
+    #[error("value {0}")]
+    Value(T),
+}
+
+#[test]
+fn test_to_json_generic() {
+    assert_eq!(
+        GenericJsonError::Value(3).to_json(),
+        r#"{"error":"Value","message":"value 3"}"#
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(index)]
+#[repr(u8)]
+enum IndexError {
+    #[error("First")]
+    First = 10,
+
+    #[error("Second: {0}")]
+    Second(i32) = 20,
+
+    #[error("Third: {message}")]
+    Third { message: String } = 5,
+}
+
+#[test]
+fn test_variant_index() {
+    assert_eq!(IndexError::First.variant_index(), 0);
+    assert_eq!(IndexError::Second(1).variant_index(), 1);
+    assert_eq!(
+        IndexError::Third {
+            message: "boom".to_string()
+        }
+        .variant_index(),
+        2
+    );
+    assert_eq!(
+        IndexError::Third {
+            message: String::new()
+        }
+        .discriminant(),
+        5
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(assert_send_sync)]
+enum ThreadSafeError<'a, T: Send + Sync> {
+    #[error("Borrowed: {0}")]
+    Borrowed(&'a str),
+
+    #[error("Shared: {0:?}")]
+    Shared(std::sync::Arc<T>),
+}
+
+#[test]
+fn test_assert_send_sync() {
+    fn spawn<E: std::error::Error + Send + Sync + 'static>(error: E) -> String {
+        std::thread::spawn(move || error.to_string())
+            .join()
+            .unwrap()
+    }
+
+    assert_eq!(
+        spawn(ThreadSafeError::<u8>::Borrowed("state")),
+        "Borrowed: state"
+    );
+    assert_eq!(
+        spawn(ThreadSafeError::<'static, u8>::Shared(std::sync::Arc::new(
+            1
+        ))),
+        "Shared: 1"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum SeverityError {
+    #[error("Retrying", severity = "warn")]
+    Retry,
+
+    #[error("Cache miss: {0}", severity = "debug")]
+    CacheMiss(String),
+
+    #[error("Fatal: {message}")]
+    Fatal { message: String },
+}
+
+#[test]
+fn test_severity() {
+    use simple_error::Severity;
+
+    assert_eq!(SeverityError::Retry.severity(), Severity::Warn);
+    assert_eq!(
+        SeverityError::CacheMiss("key".to_string()).severity(),
+        Severity::Debug
+    );
+    let error = SeverityError::Fatal {
+        message: "boom".to_string(),
+    };
+    assert_eq!(error.severity(), Severity::Error);
+    assert_eq!(error.severity().to_string(), "error");
+    assert!(SeverityError::Retry.severity() < error.severity());
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(summary)]
+enum SummaryError {
+    #[error("Connection refused")]
+    Refused,
+
+    #[error("Query failed:\n{0}\n{1}")]
+    Query(&'static str, &'static str),
+
+    #[error("{0}")]
+    Message(String),
+}
+
+#[test]
+fn test_summary() {
+    assert_eq!(SummaryError::Refused.summary(), "Connection refused");
+    assert_eq!(
+        SummaryError::Query("syntax error", "at line 1").summary(),
+        "Query failed:"
+    );
+    assert_eq!(
+        SummaryError::Message("first\nsecond".to_string()).summary(),
+        "first"
+    );
+    assert_eq!(SummaryError::Message(String::new()).summary(), "");
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(kind)]
+enum KindError<T> {
+    #[error("Timed out")]
+    Timeout,
+
+    #[error("Invalid {0}")]
+    Invalid(T),
+
+    #[error("Refused by {host}")]
+    Refused { host: &'static str },
+}
+
+#[test]
+fn test_kind() {
+    const { assert!(KindError::<u8>::Timeout.matches(KindErrorKind::Timeout)) };
+
+    assert_eq!(KindError::Invalid(1).kind(), KindErrorKind::Invalid);
+    assert!(KindError::Invalid("x").matches(KindErrorKind::Invalid));
+    assert!(!KindError::Invalid("x").matches(KindErrorKind::Timeout));
+
+    let error = KindError::<u8>::Refused { host: "localhost" };
+    assert!(error.matches(KindErrorKind::Refused));
+    assert!(!error.matches(KindErrorKind::Invalid));
+}
+
+mod restricted {
+    use simple_error_derive::SimpleError;
+
+    #[derive(Debug, SimpleError)]
+    #[simple_error(templates, vis = "pub(crate)")]
+    pub enum RestrictedError {
+        #[error("Restricted {0}")]
+        Restricted(u8),
+    }
+}
+
+#[test]
+fn test_method_visibility() {
+    let error = restricted::RestrictedError::Restricted(1);
+    assert_eq!(error.message_template(), "Restricted {0}");
+}
+
+#[derive(Debug, PartialEq, SimpleError)]
+#[simple_error(into_boxed)]
+enum BoxedError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Denied")]
+    Denied,
+}
+
+#[test]
+fn test_into_boxed() {
+    let boxed = BoxedError::NotFound("/index".to_string()).into_boxed();
+    assert_eq!(boxed.to_string(), "Not found: /index");
+    assert_eq!(
+        boxed.downcast_ref::<BoxedError>(),
+        Some(&BoxedError::NotFound("/index".to_string()))
+    );
+
+    let boxed = BoxedError::Denied.into_boxed();
+    assert_eq!(*boxed.downcast::<BoxedError>().unwrap(), BoxedError::Denied);
+}
+
+const DENIED: &str = "Access denied";
+
+#[allow(dead_code)]
+#[derive(Debug, SimpleError)]
+#[simple_error(message_table)]
+enum TableError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error(ref = DENIED)]
+    Denied,
+
+    #[cfg(any())]
+    #[error("Disabled")]
+    Disabled,
+
+    #[error("Reserved {name}")]
+    r#Type { name: String },
+}
+
+#[test]
+fn test_message_table() {
+    assert_eq!(
+        TableError::MESSAGES,
+        [
+            ("NotFound", "Not found: {0}"),
+            ("Denied", "Access denied"),
+            ("Type", "Reserved {name}"),
+        ]
+    );
+}
+
+mod custom {
+    pub trait ErrorMessage {
+        fn message(&self) -> String;
+    }
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(error_trait = custom::ErrorMessage)]
+enum CustomTraitError<T: std::fmt::Display> {
+    #[error("Invalid value: {0}")]
+    Invalid(T),
+}
+
+#[test]
+fn test_error_trait() {
+    use custom::ErrorMessage;
+
+    assert_eq!(CustomTraitError::Invalid(3).message(), "Invalid value: 3");
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(format_into)]
+enum BufferedError {
+    #[error("Not found: {0}")]
+    NotFound(&'static str),
+
+    #[error("Timed out")]
+    Timeout,
+}
+
+#[test]
+fn test_format_into() {
+    let mut buf = String::with_capacity(64);
+    BufferedError::NotFound("config").format_into(&mut buf);
+    assert_eq!(buf, "Not found: config");
+
+    buf.push_str(", ");
+    BufferedError::Timeout.format_into(&mut buf);
+    assert_eq!(buf, "Not found: config, Timed out");
+
+    buf.clear();
+    BufferedError::Timeout.format_into(&mut buf);
+    assert_eq!(buf, "Timed out");
+}
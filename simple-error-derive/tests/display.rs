@@ -0,0 +1,1484 @@
+use simple_error_derive::SimpleError;
+
+#[derive(Debug, SimpleError)]
+enum CollisionError {
+    #[error("{1} then {0}")]
+    Swapped(i32, &'static str),
+
+    #[error("{__0} and {__self_0}")]
+    Named { __0: i32, __self_0: i32 },
+}
+
+#[test]
+fn test_positional_bindings_follow_field_index() {
+    assert_eq!(
+        CollisionError::Swapped(1, "second").to_string(),
+        "second then 1"
+    );
+}
+
+#[test]
+fn test_generated_bindings_never_clash_with_field_names() {
+    assert_eq!(
+        CollisionError::Named {
+            __0: 1,
+            __self_0: 2
+        }
+        .to_string(),
+        "1 and 2"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum EscapedError {
+    #[error("{{}}")]
+    Empty,
+
+    #[error("{{}} is not {0}")]
+    Positional(i32),
+}
+
+#[test]
+fn test_escaped_braces_are_literal() {
+    assert_eq!(EscapedError::Empty.to_string(), "{}");
+    assert_eq!(EscapedError::Positional(1).to_string(), "{} is not 1");
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(templates)]
+enum ConditionalError {
+    #[error("Enabled: {0}")]
+    #[cfg(test)]
+    Enabled(#[from(no_source)] i32),
+
+    #[cfg(not(test))]
+    #[error("Disabled: {0}")]
+    Disabled(#[from(no_source)] i32),
+
+    #[error("Always")]
+    Always,
+}
+
+#[test]
+fn test_cfg_gated_variants() {
+    assert_eq!(ConditionalError::Enabled(1).to_string(), "Enabled: 1");
+    assert_eq!(ConditionalError::from(2).message_template(), "Enabled: {0}");
+    assert_eq!(ConditionalError::Always.to_string(), "Always");
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(delimiters = "%%")]
+enum PercentError {
+    #[error("Missing %name% in {config} (100%%)")]
+    Named { name: String },
+
+    #[error("Retry %0% of %1:?%")]
+    Unnamed(u8, u8),
+}
+
+#[test]
+fn test_custom_delimiters() {
+    assert_eq!(
+        PercentError::Named {
+            name: "key".to_string()
+        }
+        .to_string(),
+        "Missing key in {config} (100%)"
+    );
+    assert_eq!(PercentError::Unnamed(1, 3).to_string(), "Retry 1 of 3");
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(delimiters = "%%")]
+enum PercentShorthandError {
+    #[error(transparent)]
+    Transparent(std::io::Error),
+
+    #[error(transparent)]
+    TransparentNamed { inner: std::io::Error },
+
+    #[error(debug)]
+    Debug(Vec<u8>),
+
+    #[error(debug)]
+    DebugNamed { bytes: Vec<u8> },
+
+    #[error(ref = Self::DENIED)]
+    Ref,
+}
+
+impl PercentShorthandError {
+    const DENIED: &'static str = "Access denied (100%)";
+}
+
+#[test]
+fn test_custom_delimiters_shorthand() {
+    let error = || std::io::Error::other("boom");
+
+    assert_eq!(
+        PercentShorthandError::Transparent(error()).to_string(),
+        "boom"
+    );
+    assert_eq!(
+        PercentShorthandError::TransparentNamed { inner: error() }.to_string(),
+        "boom"
+    );
+    assert_eq!(
+        PercentShorthandError::Debug(vec![1, 2]).to_string(),
+        "[1, 2]"
+    );
+    assert_eq!(
+        PercentShorthandError::DebugNamed { bytes: vec![3] }.to_string(),
+        "[3]"
+    );
+    assert_eq!(
+        PercentShorthandError::Ref.to_string(),
+        "Access denied (100%)"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(delimiters = "%%", debug_fallback)]
+#[allow(dead_code)]
+enum PercentFallbackError {
+    #[error("%:?%")]
+    Unit,
+
+    Undocumented(u8),
+}
+
+#[test]
+fn test_custom_delimiters_debug_fallback() {
+    assert_eq!(PercentFallbackError::Unit.to_string(), "Unit");
+    assert_eq!(
+        PercentFallbackError::Undocumented(1).to_string(),
+        "Undocumented(1)"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum PathError<'a> {
+    #[error("Missing {0:path}")]
+    Owned(std::path::PathBuf),
+
+    #[error("Missing {0:path} ({0:?})")]
+    Borrowed(&'a std::path::Path),
+
+    #[error("Missing {path:path}")]
+    Named { path: std::path::PathBuf },
+}
+
+#[test]
+fn test_path_pseudo_spec() {
+    let path = std::path::Path::new("/tmp/config.toml");
+
+    assert_eq!(
+        PathError::Owned(path.to_path_buf()).to_string(),
+        "Missing /tmp/config.toml"
+    );
+    assert_eq!(
+        PathError::Borrowed(path).to_string(),
+        r#"Missing /tmp/config.toml ("/tmp/config.toml")"#
+    );
+    assert_eq!(
+        PathError::Named {
+            path: path.to_path_buf()
+        }
+        .to_string(),
+        "Missing /tmp/config.toml"
+    );
+}
+
+#[allow(dead_code)]
+#[derive(Debug, SimpleError)]
+enum WideError {
+    #[error("{11} {10:?} {0}")]
+    Wide(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, &'static str, i32),
+}
+
+#[test]
+fn test_explicit_high_indices() {
+    assert_eq!(
+        WideError::Wide(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, "ten", 11).to_string(),
+        r#"11 "ten" 0"#
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum IoError {
+    #[error("io failed: {0}")]
+    Display(std::io::Error),
+
+    #[error("io failed: {0:?}")]
+    Debug(std::io::Error),
+}
+
+#[derive(Debug, SimpleError)]
+enum GenericError<D, B> {
+    #[error("failed: {0}")]
+    Display(D),
+
+    #[error("failed: {0:?}")]
+    Debug(B),
+}
+
+#[derive(Debug)]
+struct DebugOnly;
+
+#[test]
+fn test_error_fields() {
+    let error = || std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+
+    assert_eq!(IoError::Display(error()).to_string(), "io failed: missing");
+    assert_eq!(
+        IoError::Debug(error()).to_string(),
+        r#"io failed: Custom { kind: NotFound, error: "missing" }"#
+    );
+    assert_eq!(
+        GenericError::<_, DebugOnly>::Display(error()).to_string(),
+        "failed: missing"
+    );
+    assert_eq!(
+        GenericError::<std::io::Error, _>::Debug(DebugOnly).to_string(),
+        "failed: DebugOnly"
+    );
+}
+
+const SERVICE: &str = "billing";
+
+#[derive(Debug, SimpleError)]
+enum PrefixedError {
+    #[error("{Self::PREFIX} boom in {crate::SERVICE}")]
+    Unit,
+
+    #[error("{Self::PREFIX:>8}: {0}")]
+    Unnamed(i32),
+}
+
+impl PrefixedError {
+    const PREFIX: &'static str = "[app]";
+}
+
+#[test]
+fn test_associated_const_placeholder() {
+    assert_eq!(PrefixedError::Unit.to_string(), "[app] boom in billing");
+    assert_eq!(PrefixedError::Unnamed(3).to_string(), "   [app]: 3");
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(padded)]
+enum PaddedError {
+    #[error("Padded error: {0}")]
+    Unnamed(i32),
+}
+
+#[test]
+fn test_padded() {
+    let error = PaddedError::Unnamed(1);
+
+    assert_eq!(error.to_string(), "Padded error: 1");
+    assert_eq!(format!("{error:>20}"), "     Padded error: 1");
+    assert_eq!(format!("{error:*<20}"), "Padded error: 1*****");
+    assert_eq!(format!("{error:^18.6}"), "      Padded      ");
+}
+
+#[derive(Debug, SimpleError)]
+enum ArgsError {
+    #[error("{label}: {message}", label = self.label())]
+    Named { message: String },
+
+    #[error("{0} {label:?} ({1})", self.label(), 10 * 2, label = "unit")]
+    Unit,
+
+    #[error("{} of {count}", field.len(), count = field.capacity().max(4))]
+    Mixed { field: Vec<u8> },
+}
+
+impl ArgsError {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Named { .. } => "named",
+            _ => "other",
+        }
+    }
+}
+
+#[test]
+fn test_explicit_args() {
+    assert_eq!(
+        ArgsError::Named {
+            message: "boom".to_string()
+        }
+        .to_string(),
+        "named: boom"
+    );
+    assert_eq!(ArgsError::Unit.to_string(), r#"other "unit" (20)"#);
+    assert_eq!(ArgsError::Mixed { field: vec![1, 2] }.to_string(), "2 of 4");
+}
+
+#[derive(Debug, SimpleError)]
+enum TrailingCommaError {
+    #[error("{0} ({1})", self.code(), 10 * 2,)]
+    Positional,
+
+    #[error("{x}", x = value.abs(),)]
+    Named { value: i32 },
+
+    // rustfmt drops the trailing comma of a literal-only list.
+    #[rustfmt::skip]
+    #[error("No arguments",)]
+    Empty,
+}
+
+impl TrailingCommaError {
+    fn code(&self) -> u16 {
+        404
+    }
+}
+
+#[test]
+fn test_trailing_comma_in_args() {
+    assert_eq!(TrailingCommaError::Positional.to_string(), "404 (20)");
+    assert_eq!(TrailingCommaError::Named { value: -3 }.to_string(), "3");
+    assert_eq!(TrailingCommaError::Empty.to_string(), "No arguments");
+}
+
+#[derive(Debug, SimpleError)]
+#[allow(dead_code)]
+enum ManyFieldsError {
+    #[error("{2} then {10}")]
+    Many(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8),
+}
+
+#[test]
+fn test_high_indices_bind_by_position() {
+    let error = ManyFieldsError::Many(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10);
+    assert_eq!(error.to_string(), "2 then 10");
+}
+
+#[derive(Debug, SimpleError)]
+enum NameValueError {
+    #[error = "Unit error"]
+    Unit,
+
+    #[error = "Unnamed error: {1} {0:?}"]
+    Unnamed(&'static str, i32),
+
+    #[error = "Named error: {message}"]
+    Named { message: String },
+}
+
+#[test]
+fn test_name_value_attribute() {
+    assert_eq!(NameValueError::Unit.to_string(), "Unit error");
+    assert_eq!(
+        NameValueError::Unnamed("state", 2).to_string(),
+        r#"Unnamed error: 2 "state""#
+    );
+    assert_eq!(
+        NameValueError::Named {
+            message: "critical".to_string()
+        }
+        .to_string(),
+        "Named error: critical"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum CountError {
+    #[error("[{0:1$}]")]
+    Width(i32, usize),
+
+    #[error("[{2:>1$.0$}]")]
+    Precision(usize, usize, f64),
+
+    #[error("[{value:^width$}]")]
+    Named { value: &'static str, width: usize },
+}
+
+#[test]
+fn test_count_arguments() {
+    assert_eq!(CountError::Width(7, 4).to_string(), "[   7]");
+    assert_eq!(CountError::Precision(2, 6, 1.23456).to_string(), "[  1.23]");
+    assert_eq!(
+        CountError::Named {
+            value: "ab",
+            width: 6
+        }
+        .to_string(),
+        "[  ab  ]"
+    );
+}
+
+static SUFFIX_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn expensive_suffix(code: &i32) -> String {
+    SUFFIX_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    format!("(code {code})")
+}
+
+#[derive(Debug, SimpleError)]
+enum LazyError {
+    #[error("Lazy error {suffix}", suffix = expensive_suffix(code))]
+    Named { code: i32 },
+}
+
+#[test]
+fn test_args_are_evaluated_when_displayed() {
+    use std::{fmt::Write, sync::atomic::Ordering};
+
+    let error = LazyError::Named { code: 3 };
+    assert_eq!(SUFFIX_CALLS.load(Ordering::SeqCst), 0);
+
+    assert_eq!(error.to_string(), "Lazy error (code 3)");
+    assert_eq!(SUFFIX_CALLS.load(Ordering::SeqCst), 1);
+
+    let mut message = String::new();
+    write!(message, "{error}").unwrap();
+    assert_eq!(message, "Lazy error (code 3)");
+    assert_eq!(SUFFIX_CALLS.load(Ordering::SeqCst), 2);
+}
+
+mod wrappers {
+    use std::fmt::{self, Display, LowerHex};
+
+    pub struct HexFmt<'a, T>(pub &'a T);
+
+    impl<T: LowerHex> Display for HexFmt<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "0x{:08x}", self.0)
+        }
+    }
+
+    pub struct Quoted<'a, T>(pub &'a T);
+
+    impl<T: Display> Display for Quoted<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "'{}'", self.0)
+        }
+    }
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(spec(hex = wrappers::HexFmt, quoted = wrappers::Quoted))]
+enum SpecError {
+    #[error("Invalid address {0:hex} ({0})")]
+    Address(u32),
+
+    #[error("Unknown name {name:quoted} at {offset:hex}")]
+    Name { name: String, offset: usize },
+}
+
+#[test]
+fn test_custom_specs() {
+    assert_eq!(
+        SpecError::Address(255).to_string(),
+        "Invalid address 0x000000ff (255)"
+    );
+    assert_eq!(
+        SpecError::Name {
+            name: "main".to_string(),
+            offset: 16
+        }
+        .to_string(),
+        "Unknown name 'main' at 0x00000010"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum EscapedPositionalError {
+    #[error("use {{0}} literally")]
+    Unit,
+
+    #[error("use {{0}} literally, not {0}")]
+    Unnamed(i32),
+
+    #[error("{{{0}}}")]
+    Braced(i32),
+}
+
+#[test]
+fn test_escaped_positional() {
+    assert_eq!(
+        EscapedPositionalError::Unit.to_string(),
+        "use {0} literally"
+    );
+    assert_eq!(
+        EscapedPositionalError::Unnamed(7).to_string(),
+        "use {0} literally, not 7"
+    );
+    assert_eq!(EscapedPositionalError::Braced(7).to_string(), "{7}");
+}
+
+#[derive(Debug, SimpleError)]
+enum JoinError {
+    #[error("errors: {0:join(, )}")]
+    Multiple(Vec<PathError<'static>>),
+
+    #[error("[{codes:join(|)}] {codes:?}")]
+    Codes { codes: [u16; 3] },
+}
+
+#[test]
+fn test_join() {
+    let error = |count| {
+        let errors = (0..count)
+            .map(|index| PathError::Owned(format!("/tmp/{index}").into()))
+            .collect();
+        JoinError::Multiple(errors).to_string()
+    };
+
+    assert_eq!(error(0), "errors: ");
+    assert_eq!(error(1), "errors: Missing /tmp/0");
+    assert_eq!(
+        error(3),
+        "errors: Missing /tmp/0, Missing /tmp/1, Missing /tmp/2"
+    );
+    assert_eq!(
+        JoinError::Codes {
+            codes: [404, 405, 500]
+        }
+        .to_string(),
+        "[404|405|500] [404, 405, 500]"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(by_name)]
+enum Color {
+    Red,
+    Green,
+
+    #[error("Blue ({0:#08x})")]
+    Blue(u32),
+
+    #[allow(dead_code)]
+    Custom {
+        name: String,
+    },
+}
+
+#[test]
+fn test_by_name() {
+    assert_eq!(Color::Red.to_string(), "Red");
+    assert_eq!(Color::Green.to_string(), "Green");
+    assert_eq!(Color::Blue(255).to_string(), "Blue (0x0000ff)");
+    assert_eq!(
+        Color::Custom {
+            name: "teal".to_string()
+        }
+        .to_string(),
+        "Custom"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(by_name, rename_all = "snake_case")]
+enum SnakeCaseError {
+    TooManyRequests,
+    HTTPTimeout,
+
+    #[error("Custom message")]
+    Custom,
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(by_name, rename_all = "kebab-case")]
+enum KebabCaseError {
+    TooManyRequests,
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(by_name, rename_all = "Title Case")]
+enum TitleCaseError {
+    TooManyRequests,
+}
+
+#[test]
+fn test_by_name_rename_all() {
+    assert_eq!(
+        SnakeCaseError::TooManyRequests.to_string(),
+        "too_many_requests"
+    );
+    assert_eq!(SnakeCaseError::HTTPTimeout.to_string(), "http_timeout");
+    assert_eq!(SnakeCaseError::Custom.to_string(), "Custom message");
+    assert_eq!(
+        KebabCaseError::TooManyRequests.to_string(),
+        "too-many-requests"
+    );
+    assert_eq!(
+        TitleCaseError::TooManyRequests.to_string(),
+        "Too Many Requests"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(templates, index)]
+enum ShadowedError {
+    #[error("Unit error")]
+    Unit,
+
+    #[error("Tuple error: {0}")]
+    Tuple(i32),
+
+    #[error("Named error: {message}")]
+    Named { message: String },
+}
+
+#[allow(non_snake_case, non_upper_case_globals, dead_code)]
+impl ShadowedError {
+    const Unit: u8 = 0;
+
+    fn Tuple(&self) -> &'static str {
+        "method"
+    }
+
+    fn Named() -> u8 {
+        0
+    }
+}
+
+#[test]
+fn test_variant_shadowing_associated_items() {
+    assert_eq!(ShadowedError::Unit.to_string(), "Unit error");
+    assert_eq!(ShadowedError::Tuple(1).to_string(), "Tuple error: 1");
+    assert_eq!(
+        ShadowedError::Named {
+            message: "boom".to_string()
+        }
+        .to_string(),
+        "Named error: boom"
+    );
+    assert_eq!(ShadowedError::Tuple(1).variant_index(), 1);
+}
+
+#[derive(Debug, SimpleError)]
+enum FillError {
+    #[error("[{0:*<10}] [{0:0>8}] [{0:^6}] [{0::>5}]")]
+    Code(u16),
+
+    #[error("[{name:-^9}]")]
+    Named { name: &'static str },
+}
+
+#[test]
+fn test_fill_and_align() {
+    assert_eq!(
+        FillError::Code(404).to_string(),
+        "[404*******] [00000404] [ 404  ] [::404]"
+    );
+    assert_eq!(FillError::Named { name: "abc" }.to_string(), "[---abc---]");
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(debug_fallback)]
+#[allow(dead_code)]
+enum PrototypeError<T> {
+    #[error("Documented error: {0}")]
+    Documented(i32),
+
+    Unit,
+
+    Unnamed(i32, &'static str),
+
+    Named {
+        value: T,
+    },
+}
+
+#[test]
+fn test_debug_fallback() {
+    assert_eq!(
+        PrototypeError::<u8>::Documented(1).to_string(),
+        "Documented error: 1"
+    );
+    assert_eq!(PrototypeError::<u8>::Unit.to_string(), "Unit");
+    assert_eq!(
+        PrototypeError::<u8>::Unnamed(1, "state").to_string(),
+        r#"Unnamed(1, "state")"#
+    );
+    assert_eq!(
+        PrototypeError::Named { value: [1, 2] }.to_string(),
+        "Named { value: [1, 2] }"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(prefix = "[{request_id}] ")]
+enum RequestError {
+    #[error("Timed out after {0}s")]
+    Timeout(u64),
+
+    #[error("Not found: {path}")]
+    NotFound { request_id: u32, path: String },
+
+    #[error("Forbidden")]
+    Forbidden { request_id: u32 },
+
+    #[error("Invalid request {id}")]
+    Invalid { id: u32 },
+}
+
+#[test]
+fn test_prefix() {
+    assert_eq!(RequestError::Timeout(30).to_string(), "Timed out after 30s");
+    assert_eq!(
+        RequestError::NotFound {
+            request_id: 7,
+            path: "/index".to_string()
+        }
+        .to_string(),
+        "[7] Not found: /index"
+    );
+    assert_eq!(
+        RequestError::Forbidden { request_id: 8 }.to_string(),
+        "[8] Forbidden"
+    );
+    assert_eq!(
+        RequestError::Invalid { id: 9 }.to_string(),
+        "Invalid request 9"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum RawIdentError {
+    #[error("Unexpected {r#type} ({r#type:?}) in {r#mod}")]
+    Unexpected {
+        r#type: &'static str,
+        r#mod: &'static str,
+    },
+}
+
+#[test]
+fn test_raw_identifier_fields() {
+    assert_eq!(
+        RawIdentError::Unexpected {
+            r#type: "token",
+            r#mod: "parser"
+        }
+        .to_string(),
+        r#"Unexpected token ("token") in parser"#
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(concat_errors)]
+enum StackedError {
+    #[error("Request failed:")]
+    #[error("{0}")]
+    Request(u16),
+
+    #[error("Query {}", "failed")]
+    #[error("after {attempts} attempts", severity = "warn")]
+    Query { attempts: u32 },
+}
+
+#[test]
+fn test_concat_errors() {
+    assert_eq!(
+        StackedError::Request(404).to_string(),
+        "Request failed: 404"
+    );
+    assert_eq!(
+        StackedError::Query { attempts: 3 }.to_string(),
+        "Query failed after 3 attempts"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(templates, index, json, padded, assert_send_sync)]
+enum BufferError<const N: usize> {
+    #[error("Buffer overflow: {0:?}")]
+    Overflow([u8; N]),
+
+    #[error("Expected {N} bytes, got {len}")]
+    Short { len: usize },
+}
+
+#[derive(Debug, SimpleError)]
+enum ArrayError<T, const N: usize>
+where
+    T: Copy,
+{
+    #[error("Invalid items: {0:?}")]
+    Invalid([T; N]),
+}
+
+#[test]
+fn test_const_generics() {
+    assert_eq!(
+        BufferError::Overflow([1, 2, 3]).to_string(),
+        "Buffer overflow: [1, 2, 3]"
+    );
+    assert_eq!(
+        BufferError::<4>::Short { len: 2 }.to_string(),
+        "Expected 4 bytes, got 2"
+    );
+    assert_eq!(BufferError::<4>::Short { len: 2 }.variant_index(), 1);
+    assert_eq!(
+        ArrayError::Invalid(['a', 'b']).to_string(),
+        "Invalid items: ['a', 'b']"
+    );
+
+    fn assert_error<E: std::error::Error>(_: &E) {}
+    assert_error(&ArrayError::Invalid([1.5]));
+}
+
+#[derive(Debug, SimpleError)]
+enum StatusError {
+    #[error("Status {0:x} ({0:#06X}), retry in {1}s")]
+    Status(i32, u8),
+}
+
+#[test]
+fn test_copy_positional_field() {
+    assert_eq!(
+        StatusError::Status(255, 3).to_string(),
+        "Status ff (0x00FF), retry in 3s"
+    );
+}
+
+#[allow(dead_code)]
+#[derive(Debug, SimpleError)]
+enum LookupError {
+    #[error("Missing key {key:?}")]
+    Missing { key: String, attempts: u32 },
+}
+
+#[test]
+fn test_named_debug_placeholder() {
+    assert_eq!(
+        LookupError::Missing {
+            key: "id".to_string(),
+            attempts: 2
+        }
+        .to_string(),
+        r#"Missing key "id""#
+    );
+}
+
+mod messages {
+    pub const TIMEOUT: &str = "Timed out";
+    pub const LITERAL: &str = "Literal {braces}";
+}
+
+#[allow(dead_code)]
+#[derive(Debug, SimpleError)]
+#[simple_error(templates)]
+enum SharedMessageError {
+    #[error(ref = messages::TIMEOUT)]
+    Timeout,
+
+    #[error(ref = messages::LITERAL, severity = "warn")]
+    Literal(u8),
+
+    #[error(ref = Self::INTERNAL)]
+    Internal { code: u16 },
+}
+
+impl SharedMessageError {
+    const INTERNAL: &'static str = "Internal error";
+}
+
+#[test]
+fn test_const_message() {
+    assert_eq!(SharedMessageError::Timeout.to_string(), "Timed out");
+    assert_eq!(
+        SharedMessageError::Literal(1).to_string(),
+        "Literal {braces}"
+    );
+    assert_eq!(
+        SharedMessageError::Internal { code: 500 }.to_string(),
+        "Internal error"
+    );
+    assert_eq!(SharedMessageError::Timeout.message_template(), "Timed out");
+    assert_eq!(
+        SharedMessageError::Internal { code: 500 }.message_template(),
+        "Internal error"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum EmptySpecError {
+    #[error("{:}-{0:}/{:}{:}")]
+    Parts(u8, u8, u8),
+}
+
+#[test]
+fn test_empty_spec() {
+    assert_eq!(EmptySpecError::Parts(1, 2, 3).to_string(), "1-1/23");
+}
+
+#[derive(Debug, SimpleError)]
+enum UploadError {
+    #[error("Upload failed (retryable: {0:yesno}, {0})")]
+    Failed(bool),
+
+    #[error("Upload rejected (authenticated: {authenticated:yesno})")]
+    Rejected { authenticated: bool },
+
+    #[error("Upload skipped (cached: {:yesno})", cached.is_some())]
+    Skipped { cached: Option<u32> },
+}
+
+#[test]
+fn test_yesno() {
+    assert_eq!(
+        UploadError::Failed(true).to_string(),
+        "Upload failed (retryable: yes, true)"
+    );
+    assert_eq!(
+        UploadError::Failed(false).to_string(),
+        "Upload failed (retryable: no, false)"
+    );
+    assert_eq!(
+        UploadError::Rejected {
+            authenticated: false
+        }
+        .to_string(),
+        "Upload rejected (authenticated: no)"
+    );
+    assert_eq!(
+        UploadError::Skipped { cached: Some(1) }.to_string(),
+        "Upload skipped (cached: yes)"
+    );
+}
+
+#[derive(Debug, Default)]
+struct Counted(std::cell::Cell<u32>);
+
+impl std::fmt::Display for Counted {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.set(self.0.get() + 1);
+        write!(f, "counted")
+    }
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(dedup_args)]
+enum DedupError {
+    #[error("{0} {0:>9} {0} {1}")]
+    Unnamed(Counted, Counted),
+
+    #[error("{value}/{value}")]
+    Named { value: Counted },
+
+    #[error("{value}, {value}, {value:?}")]
+    Debugged { value: Counted },
+
+    #[error("{0:+} {0} [{0:>4}]")]
+    Flagged(i32),
+}
+
+#[test]
+fn test_dedup_args() {
+    let error = DedupError::Unnamed(Counted::default(), Counted::default());
+    assert_eq!(error.to_string(), "counted counted counted counted");
+    let DedupError::Unnamed(first, second) = &error else {
+        unreachable!();
+    };
+    assert_eq!((first.0.get(), second.0.get()), (3, 1));
+
+    let error = DedupError::Named {
+        value: Counted::default(),
+    };
+    assert_eq!(error.to_string(), "counted/counted");
+    let DedupError::Named { value } = &error else {
+        unreachable!();
+    };
+    assert_eq!(value.0.get(), 1);
+
+    let error = DedupError::Debugged {
+        value: Counted::default(),
+    };
+    assert_eq!(
+        error.to_string(),
+        "counted, counted, Counted(Cell { value: 2 })"
+    );
+
+    assert_eq!(DedupError::Flagged(7).to_string(), "+7 7 [   7]");
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    header: Header,
+}
+
+#[derive(Debug)]
+struct Header {
+    name: &'static str,
+    size: (usize, usize),
+}
+
+#[derive(Debug, SimpleError)]
+enum NestedFieldError {
+    #[error("Status {0.status} ({0.status:#x}) from {1}")]
+    Status(Response, &'static str),
+
+    #[error("Header {0.header.name:?} of {0.header.size.1} bytes")]
+    Header(Response),
+
+    #[error("Invalid {response.header.name} for {}", response.status)]
+    Named { response: Response },
+}
+
+#[test]
+fn test_nested_fields() {
+    let response = || Response {
+        status: 404,
+        header: Header {
+            name: "Content-Length",
+            size: (1, 16),
+        },
+    };
+
+    assert_eq!(
+        NestedFieldError::Status(response(), "proxy").to_string(),
+        "Status 404 (0x194) from proxy"
+    );
+    assert_eq!(
+        NestedFieldError::Header(response()).to_string(),
+        r#"Header "Content-Length" of 16 bytes"#
+    );
+    assert_eq!(
+        NestedFieldError::Named {
+            response: response()
+        }
+        .to_string(),
+        "Invalid Content-Length for 404"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum TimeoutError {
+    #[error("Timed out after {0:duration} ({0:?})")]
+    Elapsed(std::time::Duration),
+
+    #[error("Retry in {delay:duration}, or {:duration}", *delay * 2)]
+    Retry { delay: std::time::Duration },
+}
+
+#[test]
+fn test_duration() {
+    use std::time::Duration;
+
+    assert_eq!(
+        TimeoutError::Elapsed(Duration::from_millis(5_200)).to_string(),
+        "Timed out after 5s 200ms (5.2s)"
+    );
+    assert_eq!(
+        TimeoutError::Elapsed(Duration::from_millis(200)).to_string(),
+        "Timed out after 200ms (200ms)"
+    );
+    assert_eq!(
+        TimeoutError::Retry {
+            delay: Duration::from_secs(45)
+        }
+        .to_string(),
+        "Retry in 45s, or 1m 30s"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum BracedCodeError {
+    #[error("code {0}}}")]
+    Trailing(u16),
+
+    #[error("{{{0}}}")]
+    Enclosed(u16),
+
+    #[error("{{{name}}}{{}}")]
+    Named { name: &'static str },
+}
+
+#[test]
+fn test_placeholder_followed_by_escaped_brace() {
+    assert_eq!(BracedCodeError::Trailing(7).to_string(), "code 7}");
+    assert_eq!(BracedCodeError::Enclosed(7).to_string(), "{7}");
+    assert_eq!(BracedCodeError::Named { name: "x" }.to_string(), "{x}{}");
+}
+
+#[derive(Debug, SimpleError)]
+enum PrecisionError {
+    #[error("Ratio {0:.3} ({0:.0}, {0:>9.2e})")]
+    Ratio(f64),
+
+    #[error("Ratio {value:.digits$}")]
+    Named { value: f64, digits: usize },
+}
+
+#[test]
+fn test_precision() {
+    assert_eq!(
+        PrecisionError::Ratio(1.23456).to_string(),
+        "Ratio 1.235 (1,    1.23e0)"
+    );
+    assert_eq!(
+        PrecisionError::Named {
+            value: 1.23456,
+            digits: 1
+        }
+        .to_string(),
+        "Ratio 1.2"
+    );
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Opaque {
+    id: u32,
+}
+
+#[derive(Debug, SimpleError)]
+enum DebugFieldError {
+    #[error(debug)]
+    Opaque(Opaque),
+
+    #[error(debug)]
+    Named { values: Vec<&'static str> },
+}
+
+#[test]
+fn test_debug_field() {
+    assert_eq!(
+        DebugFieldError::Opaque(Opaque { id: 7 }).to_string(),
+        "Opaque { id: 7 }"
+    );
+    assert_eq!(
+        DebugFieldError::Named {
+            values: vec!["a", "b"]
+        }
+        .to_string(),
+        r#"["a", "b"]"#
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum DumpError {
+    #[error("{:?}")]
+    Unit,
+
+    #[error("{:?}")]
+    Wrapped(u8),
+}
+
+#[test]
+fn test_unit_variant_debug_template() {
+    assert_eq!(DumpError::Unit.to_string(), "Unit");
+    assert_eq!(DumpError::Wrapped(3).to_string(), "3");
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+#[allow(deprecated)]
+fn test_legacy_description() {
+    use std::error::Error;
+
+    #[derive(Debug, SimpleError)]
+    enum LegacyError {
+        #[error("Legacy error: {0}")]
+        Legacy(u8),
+    }
+
+    assert_eq!(LegacyError::Legacy(1).description(), "Legacy error: {0}");
+}
+
+#[derive(Debug)]
+struct Unprintable;
+
+impl std::fmt::Display for Unprintable {
+    fn fmt(&self, _: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(catch_all = "Unknown", propagate_alternate)]
+enum CatchAllError {
+    #[error("{0}")]
+    Unprintable(Unprintable),
+
+    #[error("partial output {0}")]
+    Partial(Unprintable),
+
+    #[error("Printable {0}")]
+    Printable(u8),
+
+    #[error("Nested {0:?}")]
+    Nested(Vec<u8>),
+
+    #[error("Unknown error")]
+    Unknown,
+}
+
+#[test]
+fn test_catch_all() {
+    assert_eq!(
+        CatchAllError::Unprintable(Unprintable).to_string(),
+        "Unknown error"
+    );
+    assert_eq!(
+        CatchAllError::Partial(Unprintable).to_string(),
+        "Unknown error"
+    );
+    assert_eq!(CatchAllError::Printable(1).to_string(), "Printable 1");
+    assert_eq!(CatchAllError::Unknown.to_string(), "Unknown error");
+    assert_eq!(
+        format!("{:#}", CatchAllError::Nested(vec![1])),
+        "Nested [\n    1,\n]"
+    );
+}
+
+#[derive(Debug)]
+struct Inner;
+
+impl std::fmt::Display for Inner {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if f.alternate() {
+            f.write_str("inner (alternate)")
+        } else {
+            f.write_str("inner")
+        }
+    }
+}
+
+#[derive(Debug, SimpleError)]
+enum AlternateError {
+    #[error("Wrapped: {0:#}")]
+    Alternate(Inner),
+
+    #[error("Wrapped: {0}")]
+    Plain(Inner),
+}
+
+#[test]
+fn test_alternate_spec_reaches_inner_error() {
+    assert_eq!(
+        AlternateError::Alternate(Inner).to_string(),
+        "Wrapped: inner (alternate)"
+    );
+    assert_eq!(AlternateError::Plain(Inner).to_string(), "Wrapped: inner");
+}
+
+#[derive(Debug, SimpleError)]
+enum MixedAccessError {
+    #[error("{a} / {self.a}")]
+    Named { a: u8 },
+
+    #[error("{0} / {self.0} / {self.1:?}")]
+    Unnamed(u8, &'static str),
+}
+
+#[test]
+fn test_mixed_field_access() {
+    assert_eq!(MixedAccessError::Named { a: 1 }.to_string(), "1 / 1");
+    assert_eq!(
+        MixedAccessError::Unnamed(2, "b").to_string(),
+        "2 / 2 / \"b\""
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum IncludedError {
+    #[error(include = "tests/messages/timeout.txt")]
+    Timeout(u64),
+
+    #[error(include = "tests/messages/timeout.txt", self.seconds())]
+    Slow,
+}
+
+impl IncludedError {
+    fn seconds(&self) -> u64 {
+        5
+    }
+}
+
+#[test]
+fn test_included_template() {
+    assert_eq!(
+        IncludedError::Timeout(30).to_string(),
+        "Timed out after 30s"
+    );
+    assert_eq!(IncludedError::Slow.to_string(), "Timed out after 5s");
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Request {
+    id: u8,
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(propagate_alternate)]
+enum AlternateDebugError {
+    #[error("Invalid request: {0:?}")]
+    Invalid(Request),
+
+    #[error("Invalid id: {0:>4?} {0:#?}")]
+    Id(u8),
+}
+
+#[test]
+fn test_propagate_alternate() {
+    let error = AlternateDebugError::Invalid(Request { id: 1 });
+    assert_eq!(error.to_string(), "Invalid request: Request { id: 1 }");
+    assert_eq!(
+        format!("{error:#}"),
+        "Invalid request: Request {\n    id: 1,\n}"
+    );
+
+    let error = AlternateDebugError::Id(7);
+    assert_eq!(error.to_string(), "Invalid id:    7 7");
+    assert_eq!(format!("{error:#}"), "Invalid id:    7 7");
+}
+
+#[derive(Debug, SimpleError)]
+enum NamedBindingError {
+    #[error("{0}:{1}: {2}")]
+    #[name(0 = "path", 1 = "line")]
+    Parse(&'static str, u32, &'static str),
+}
+
+#[test]
+fn test_named_bindings() {
+    assert_eq!(
+        NamedBindingError::Parse("main.rs", 3, "unexpected token").to_string(),
+        "main.rs:3: unexpected token"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum FixedArrayError {
+    #[error("Bytes: {0:?}")]
+    Bytes([u8; 4]),
+
+    #[error("Hex: {0:x?} {1:#X?}")]
+    Hex([u8; 4], [u16; 1]),
+}
+
+#[test]
+fn test_fixed_size_array_fields() {
+    assert_eq!(
+        FixedArrayError::Bytes([1, 2, 3, 255]).to_string(),
+        "Bytes: [1, 2, 3, 255]"
+    );
+    assert_eq!(
+        FixedArrayError::Hex([1, 2, 3, 255], [0xab]).to_string(),
+        "Hex: [1, 2, 3, ff] [\n    0xAB,\n]"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum WrappingError<T> {
+    #[error("parse error: {0}")]
+    Parse(T),
+}
+
+#[test]
+fn test_display_only_field_is_not_bounded_by_error() {
+    fn assert_error<E: std::error::Error>(error: &E) -> String {
+        error.to_string()
+    }
+
+    // `i32` is `Display`, but not an `Error`.
+    assert_eq!(assert_error(&WrappingError::Parse(42)), "parse error: 42");
+
+    let error = WrappingError::Parse("x".parse::<u8>().unwrap_err());
+    assert_eq!(
+        assert_error(&error),
+        "parse error: invalid digit found in string"
+    );
+    assert!(std::error::Error::source(&error).is_none());
+}
+
+mod numbers {
+    pub fn group_digits(value: &u64) -> String {
+        let digits = value.to_string();
+        let mut grouped = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(num = numbers::group_digits)]
+enum QuotaError {
+    #[error("Quota exceeded: {used:num} of {limit:num} bytes")]
+    Exceeded { used: u64, limit: u64 },
+
+    #[error("Invalid quota: {0:num} ({0})")]
+    Invalid(u64),
+}
+
+#[test]
+fn test_num_spec() {
+    let error = QuotaError::Exceeded {
+        used: 1234567,
+        limit: 1000,
+    };
+    assert_eq!(
+        error.to_string(),
+        "Quota exceeded: 1,234,567 of 1,000 bytes"
+    );
+    assert_eq!(
+        QuotaError::Invalid(123).to_string(),
+        "Invalid quota: 123 (123)"
+    );
+}
+
+#[derive(Debug)]
+struct StatusCode(u16);
+
+impl StatusCode {
+    fn as_u16(&self) -> u16 {
+        self.0
+    }
+
+    fn canonical_reason(&self) -> &'static str {
+        match self.0 {
+            404 => "Not Found",
+            _ => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, SimpleError)]
+enum HttpError {
+    #[error("{status.as_u16()} {status.canonical_reason()}")]
+    Http { status: StatusCode },
+
+    #[error("Redirected with {0.as_u16():>5}")]
+    Redirect(StatusCode),
+}
+
+#[test]
+fn test_method_call_on_field() {
+    let error = HttpError::Http {
+        status: StatusCode(404),
+    };
+    assert_eq!(error.to_string(), "404 Not Found");
+    assert_eq!(
+        HttpError::Redirect(StatusCode(301)).to_string(),
+        "Redirected with   301"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum BothWaysError<T> {
+    #[error("Invalid: {0} ({0:?})")]
+    Invalid(T),
+}
+
+#[test]
+fn test_field_formatted_both_ways() {
+    assert_eq!(
+        BothWaysError::Invalid("name").to_string(),
+        "Invalid: name (\"name\")"
+    );
+}
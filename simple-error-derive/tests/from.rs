@@ -0,0 +1,131 @@
+use simple_error_derive::SimpleError;
+
+#[derive(Debug, SimpleError)]
+enum MessageError {
+    #[error("Parse error: {0}")]
+    Parse(#[from] std::num::ParseIntError),
+
+    #[error("{0}")]
+    Other(#[from] String),
+}
+
+#[derive(Debug, SimpleError)]
+enum NamedMessageError {
+    #[error("{message}")]
+    Other {
+        #[from]
+        message: String,
+    },
+}
+
+#[derive(Debug, SimpleError)]
+enum BorrowedMessageError<'a> {
+    #[error("{0}")]
+    Other(#[from] &'a str),
+}
+
+#[test]
+fn test_from_string_and_str() {
+    assert_eq!(MessageError::from("boom").to_string(), "boom");
+    assert_eq!(MessageError::from("boom".to_string()).to_string(), "boom");
+    assert_eq!(NamedMessageError::from("boom").to_string(), "boom");
+    assert_eq!(
+        NamedMessageError::from("boom".to_string()).to_string(),
+        "boom"
+    );
+    assert_eq!(BorrowedMessageError::from("boom").to_string(), "boom");
+}
+
+#[test]
+fn test_from_source() {
+    fn parse(value: &str) -> Result<i32, MessageError> {
+        Ok(value.parse::<i32>()?)
+    }
+
+    assert_eq!(
+        parse("x").unwrap_err().to_string(),
+        "Parse error: invalid digit found in string"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum ContextError {
+    #[error("io error: {source} ({context:?})")]
+    Io {
+        #[from]
+        source: std::io::Error,
+        context: String,
+    },
+
+    #[error("parse error: {1} at {0}")]
+    Parse(usize, #[from] std::num::ParseIntError, Option<String>),
+}
+
+#[test]
+fn test_from_with_default_fields() {
+    let error = ContextError::from(std::io::Error::other("boom"));
+    assert_eq!(error.to_string(), r#"io error: boom ("")"#);
+
+    let error = ContextError::from("x".parse::<i32>().unwrap_err());
+    assert!(matches!(error, ContextError::Parse(0, _, None)));
+    assert_eq!(
+        error.to_string(),
+        "parse error: invalid digit found in string at 0"
+    );
+}
+
+#[derive(Debug, SimpleError)]
+enum SourcesError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("int error: {0}")]
+    Int(#[from] std::num::ParseIntError),
+
+    #[error("float error: {0}")]
+    Float(#[from] std::num::ParseFloatError),
+}
+
+#[test]
+fn test_from_distinct_sources() {
+    fn parse(value: &str) -> Result<(i32, f64), SourcesError> {
+        Ok((value.parse()?, value.parse()?))
+    }
+
+    assert!(matches!(
+        SourcesError::from(std::io::Error::other("boom")),
+        SourcesError::Io(_)
+    ));
+    assert!(matches!(parse("x").unwrap_err(), SourcesError::Int(_)));
+    assert!(matches!(
+        SourcesError::from("x".parse::<f64>().unwrap_err()),
+        SourcesError::Float(_)
+    ));
+}
+
+#[derive(Debug, SimpleError)]
+enum BoxedFromError {
+    #[error("Other error: {0}")]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[test]
+fn test_from_boxed_source() {
+    fn parse(text: &str) -> Result<i32, BoxedFromError> {
+        Ok(text.parse::<i32>().map_err(Box::from)?)
+    }
+
+    fn open(path: &str) -> Result<std::fs::File, BoxedFromError> {
+        Ok(std::fs::File::open(path).map_err(Box::from)?)
+    }
+
+    let error = parse("x").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Other error: invalid digit found in string"
+    );
+
+    let error = open("/nonexistent/path").unwrap_err();
+    let BoxedFromError::Other(source) = &error;
+    assert!(source.is::<std::io::Error>());
+}
@@ -0,0 +1,77 @@
+#![no_implicit_prelude]
+
+use ::simple_error_derive::SimpleError;
+
+#[derive(::std::fmt::Debug, SimpleError)]
+enum StdError {
+    #[error("Unnamed error: {0:?}, {1}")]
+    Unnamed(i32, &'static str),
+
+    #[error("Named error: {message}")]
+    Named { message: &'static str },
+}
+
+#[derive(::core::fmt::Debug, SimpleError)]
+#[simple_error(no_std)]
+enum CoreError<T> {
+    #[error("Unnamed error: {0:x}")]
+    Unnamed(T),
+}
+
+#[test]
+fn test_fully_qualified_output() {
+    use ::std::string::ToString;
+
+    ::std::assert_eq!(
+        StdError::Unnamed(1, "state").to_string(),
+        "Unnamed error: 1, state"
+    );
+    ::std::assert_eq!(
+        StdError::Named { message: "boom" }.to_string(),
+        "Named error: boom"
+    );
+    ::std::assert_eq!(CoreError::Unnamed(255).to_string(), "Unnamed error: ff");
+}
+
+#[test]
+fn test_shadowed_names_in_function() {
+    use ::std::convert::From;
+    use ::std::string::ToString;
+
+    #[allow(unused_macros)]
+    macro_rules! write {
+        ($($tokens:tt)*) => {
+            ::std::compile_error!("shadowed `write!` was called")
+        };
+    }
+
+    #[allow(dead_code)]
+    mod std {}
+
+    #[allow(dead_code)]
+    fn write() {}
+
+    #[derive(::std::fmt::Debug, SimpleError)]
+    #[simple_error(templates, json, padded)]
+    enum LocalError {
+        #[error("Parse error: {0}")]
+        Parse(#[from] ::std::num::ParseIntError),
+
+        #[error("{message} ({code})")]
+        Other {
+            #[from]
+            message: ::std::string::String,
+            code: i32,
+        },
+    }
+
+    ::std::assert_eq!(LocalError::from("boom").to_string(), "boom (0)");
+    ::std::assert_eq!(
+        LocalError::from("boom").to_json(),
+        r#"{"error":"Other","message":"boom (0)"}"#
+    );
+    ::std::assert_eq!(
+        ::std::format!("{:>9}", LocalError::from("boom")),
+        " boom (0)"
+    );
+}
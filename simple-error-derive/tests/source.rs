@@ -0,0 +1,340 @@
+use std::error::Error;
+
+use simple_error_derive::SimpleError;
+
+#[derive(Debug, SimpleError)]
+enum SourceError {
+    #[error("Unit error")]
+    Unit,
+
+    #[error("io error")]
+    Io(#[source] std::io::Error),
+
+    #[error("parse error in {path}")]
+    Parse {
+        path: String,
+        source: std::num::ParseIntError,
+    },
+
+    #[error("dynamic error")]
+    Dynamic(#[source] Box<dyn Error + Send + Sync>),
+}
+
+#[test]
+fn test_source() {
+    assert!(SourceError::Unit.source().is_none());
+
+    let error = SourceError::Io(std::io::Error::other("boom"));
+    assert_eq!(error.source().unwrap().to_string(), "boom");
+
+    let error = SourceError::Parse {
+        path: "config".to_string(),
+        source: "x".parse::<i32>().unwrap_err(),
+    };
+    assert!(error.source().unwrap().is::<std::num::ParseIntError>());
+}
+
+#[test]
+fn test_boxed_source() {
+    let error = SourceError::Dynamic("boxed".into());
+    assert_eq!(error.source().unwrap().to_string(), "boxed");
+
+    let error = SourceError::Dynamic(Box::new(std::io::Error::other("boom")));
+    assert!(error.source().unwrap().is::<std::io::Error>());
+}
+
+#[derive(Debug, SimpleError)]
+enum GenericSourceError<E> {
+    #[error("wrapped error")]
+    Wrapped(#[source] E),
+}
+
+#[test]
+fn test_generic_source() {
+    let error = GenericSourceError::Wrapped(std::io::Error::other("boom"));
+    assert_eq!(error.source().unwrap().to_string(), "boom");
+}
+
+#[derive(Debug, SimpleError)]
+#[allow(dead_code)]
+enum HiddenSourceError {
+    #[error("internal error")]
+    Internal {
+        #[from(no_source)]
+        source: std::io::Error,
+    },
+
+    #[error("parse error")]
+    Parse(
+        #[from(no_source)]
+        #[source]
+        std::num::ParseIntError,
+    ),
+
+    #[error("visible error")]
+    Visible { source: std::fmt::Error },
+
+    #[error("float error")]
+    Float(#[from(no_source)] std::num::ParseFloatError),
+}
+
+#[test]
+fn test_from_without_source() {
+    let error = HiddenSourceError::from(std::io::Error::other("boom"));
+    assert!(matches!(error, HiddenSourceError::Internal { .. }));
+    assert!(error.source().is_none());
+
+    let error = HiddenSourceError::from("x".parse::<i32>().unwrap_err());
+    assert!(error.source().is_none());
+
+    let error = HiddenSourceError::from("x".parse::<f64>().unwrap_err());
+    assert!(error.source().is_none());
+
+    let error = HiddenSourceError::Visible {
+        source: std::fmt::Error,
+    };
+    assert!(error.source().is_some());
+}
+
+#[derive(Debug, SimpleError)]
+enum FromSourceError {
+    #[error("parse error")]
+    Parse(#[from] std::num::ParseIntError),
+
+    #[error("io error")]
+    Io {
+        #[from]
+        inner: std::io::Error,
+    },
+
+    #[error("{0}")]
+    Message(#[from] String),
+}
+
+#[test]
+fn test_from_implies_source() {
+    let error = FromSourceError::from("x".parse::<i32>().unwrap_err());
+    assert!(error.source().unwrap().is::<std::num::ParseIntError>());
+
+    let error = FromSourceError::from(std::io::Error::other("boom"));
+    assert_eq!(error.source().unwrap().to_string(), "boom");
+
+    assert!(FromSourceError::from("message").source().is_none());
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(chain)]
+enum ChainError {
+    #[error("loading the config")]
+    Config(#[source] SourceError),
+
+    #[error("unit error")]
+    Unit,
+}
+
+#[test]
+fn test_chain() {
+    let error = ChainError::Config(SourceError::Io(std::io::Error::other("boom")));
+    let chain = error
+        .chain()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(chain, ["loading the config", "io error", "boom"]);
+    assert_eq!(ChainError::Unit.chain().count(), 1);
+}
+
+#[derive(Debug, SimpleError)]
+enum InlineSourceError {
+    #[error("db error: {source}")]
+    Db(#[source] std::io::Error),
+
+    #[error("parse error in {path}: {source:?}")]
+    Parse {
+        path: &'static str,
+        #[source]
+        cause: std::num::ParseIntError,
+    },
+
+    #[error("io error: {source}")]
+    Io { source: std::io::Error },
+}
+
+#[test]
+fn test_inline_source() {
+    let error = InlineSourceError::Db(std::io::Error::other("closed"));
+    assert_eq!(error.to_string(), "db error: closed");
+    assert_eq!(error.source().unwrap().to_string(), "closed");
+
+    let error = InlineSourceError::Parse {
+        path: "config",
+        cause: "x".parse::<i32>().unwrap_err(),
+    };
+    assert_eq!(
+        error.to_string(),
+        "parse error in config: ParseIntError { kind: InvalidDigit }"
+    );
+    assert!(error.source().unwrap().is::<std::num::ParseIntError>());
+
+    let error = InlineSourceError::Io {
+        source: std::io::Error::other("boom"),
+    };
+    assert_eq!(error.to_string(), "io error: boom");
+    assert_eq!(error.source().unwrap().to_string(), "boom");
+}
+
+#[derive(Debug, SimpleError)]
+enum BoxedSourceError<E> {
+    #[error("concrete error")]
+    Concrete(#[source] Box<SourceError>),
+
+    #[error("generic error")]
+    Generic(#[source] Box<E>),
+}
+
+#[test]
+fn test_display_through_references_and_boxes() {
+    fn display(error: impl std::fmt::Display) -> String {
+        error.to_string()
+    }
+
+    let error = SourceError::Io(std::io::Error::other("boom"));
+    assert_eq!(display(&error), "io error");
+
+    let owned: Box<SourceError> = Box::new(error);
+    assert_eq!(display(&owned), "io error");
+    assert_eq!(owned.source().unwrap().to_string(), "boom");
+    assert_eq!(display_chain(&owned), ["io error", "boom"]);
+    assert_eq!(display(&*owned), "io error");
+
+    let boxed: Box<dyn Error> = owned;
+    assert_eq!(display(&boxed), "io error");
+    assert_eq!(boxed.source().unwrap().to_string(), "boom");
+}
+
+#[test]
+fn test_boxed_concrete_source() {
+    let error = BoxedSourceError::<std::io::Error>::Concrete(Box::new(SourceError::Unit));
+    assert!(error.source().unwrap().is::<SourceError>());
+    assert_eq!(error.source().unwrap().to_string(), "Unit error");
+
+    let error = BoxedSourceError::Generic(Box::new(std::io::Error::other("boom")));
+    assert!(error.source().unwrap().is::<std::io::Error>());
+    assert_eq!(display_chain(&error), ["generic error", "boom"]);
+}
+
+fn display_chain(error: &(dyn Error + 'static)) -> Vec<String> {
+    std::iter::successors(Some(error), |&error| error.source())
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[derive(Debug, SimpleError)]
+enum TransparentError {
+    #[error(transparent)]
+    Source(#[from] SourceError),
+
+    #[error(transparent)]
+    Io {
+        #[from]
+        inner: std::io::Error,
+    },
+
+    #[error(transparent)]
+    Dynamic(Box<dyn Error + Send + Sync>),
+}
+
+#[test]
+fn test_transparent_from() {
+    fn parse(text: &str) -> Result<i32, TransparentError> {
+        let value = text.parse::<i32>().map_err(|source| SourceError::Parse {
+            path: "config".to_string(),
+            source,
+        })?;
+        Ok(value)
+    }
+
+    let error = parse("x").unwrap_err();
+    assert_eq!(error.to_string(), "parse error in config");
+    assert!(error.source().unwrap().is::<std::num::ParseIntError>());
+    assert_eq!(
+        display_chain(&error),
+        ["parse error in config", "invalid digit found in string"]
+    );
+
+    let error = TransparentError::from(std::io::Error::other("boom"));
+    assert_eq!(error.to_string(), "boom");
+    assert!(error.source().is_none());
+
+    let error = TransparentError::Dynamic(Box::new(SourceError::Io(std::io::Error::other("boom"))));
+    assert_eq!(display_chain(&error), ["io error", "boom"]);
+}
+
+#[derive(Debug, SimpleError)]
+#[simple_error(show_source)]
+enum ShowSourceError {
+    #[error("Reading failed")]
+    Read(#[source] std::io::Error),
+
+    #[error("Loading {path} failed")]
+    Load {
+        path: &'static str,
+        source: SourceError,
+    },
+
+    #[error("Parsing failed: {source}")]
+    Parse { source: std::num::ParseIntError },
+
+    #[error("Nothing to read")]
+    Empty,
+
+    #[error(transparent)]
+    Other(Box<dyn Error + Send + Sync>),
+}
+
+#[test]
+fn test_show_source() {
+    let error = ShowSourceError::Read(std::io::Error::other("boom"));
+    assert_eq!(error.to_string(), "Reading failed (caused by: boom)");
+
+    let error = ShowSourceError::Load {
+        path: "config",
+        source: SourceError::Unit,
+    };
+    assert_eq!(
+        error.to_string(),
+        format!("Loading config failed (caused by: {})", SourceError::Unit)
+    );
+
+    let error = ShowSourceError::Parse {
+        source: "x".parse::<i32>().unwrap_err(),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Parsing failed: invalid digit found in string"
+    );
+
+    assert_eq!(ShowSourceError::Empty.to_string(), "Nothing to read");
+
+    let error = ShowSourceError::Other(Box::new(std::io::Error::other("boom")));
+    assert_eq!(error.to_string(), "boom");
+}
+
+#[derive(Debug, SimpleError)]
+enum MixedGenericsError<S, T> {
+    #[error("Failed")]
+    Failed(#[source] S),
+
+    #[error("Invalid value: {0}")]
+    Invalid(T),
+}
+
+#[test]
+fn test_generic_source_and_display_only_field() {
+    // Only the source needs to be an `Error`, `i32` is just `Display`.
+    let error = MixedGenericsError::<std::io::Error, i32>::Invalid(3);
+    assert_eq!(error.to_string(), "Invalid value: 3");
+    assert!(error.source().is_none());
+
+    let error = MixedGenericsError::<_, i32>::Failed(std::io::Error::other("boom"));
+    assert_eq!(error.source().unwrap().to_string(), "boom");
+}
@@ -0,0 +1,28 @@
+//! Times the parsing of a 10k-character format string, e.g. `cargo bench -p simple-error`.
+
+use std::{hint::black_box, time::Instant};
+
+use simple_error::Interpolate;
+use syn::{parse_quote, Variant};
+
+fn main() {
+    let variant: Variant = parse_quote!(Long {
+        name: String,
+        code: u16
+    });
+    let chunk = "{name} {{é}} {code:>8} ";
+    let text = chunk.repeat(10_000 / chunk.len() + 1);
+
+    let iterations = 200;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(Interpolate::parse(black_box(&text), &variant));
+    }
+
+    let elapsed = start.elapsed();
+    println!(
+        "parse {} bytes: {:?} per iteration",
+        text.len(),
+        elapsed / iterations
+    );
+}
@@ -0,0 +1,74 @@
+use std::{error::Error, fmt};
+
+/// An error wrapped with a message describing what was being done when it occurred.
+///
+/// The wrapped error is returned by `source()`, so that walking the sources of the
+/// outermost error yields every context down to the original error.
+#[derive(Debug)]
+pub struct WithContext<E> {
+    context: String,
+    source: E,
+}
+
+impl<E> WithContext<E> {
+    /// Wrap the error with the given context.
+    pub fn new(context: impl Into<String>, source: E) -> Self {
+        Self {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// The context the error was wrapped with.
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+
+    /// The wrapped error.
+    pub fn get_ref(&self) -> &E {
+        &self.source
+    }
+
+    /// Unwrap the error, dropping its context.
+    pub fn into_inner(self) -> E {
+        self.source
+    }
+}
+
+impl<E> fmt::Display for WithContext<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.context)
+    }
+}
+
+impl<E: Error + 'static> Error for WithContext<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Attach context to the error of a `Result`, in the manner of `anyhow::Context`.
+pub trait Context<T, E> {
+    /// Wrap the error, if any, with the given context.
+    fn context(self, context: impl Into<String>) -> Result<T, WithContext<E>>;
+
+    /// Wrap the error, if any, with the context returned by the closure, which is only
+    /// called on error.
+    fn with_context<C: Into<String>>(
+        self,
+        context: impl FnOnce() -> C,
+    ) -> Result<T, WithContext<E>>;
+}
+
+impl<T, E: Error> Context<T, E> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, WithContext<E>> {
+        self.map_err(|source| WithContext::new(context, source))
+    }
+
+    fn with_context<C: Into<String>>(
+        self,
+        context: impl FnOnce() -> C,
+    ) -> Result<T, WithContext<E>> {
+        self.map_err(|source| WithContext::new(context(), source))
+    }
+}
@@ -0,0 +1,36 @@
+use std::{fmt, time::Duration};
+
+/// Displays a `Duration` in a human-readable form, as done by `{0:duration}`, e.g. `5s 200ms`
+/// or `1h 30m`. Durations under a millisecond are displayed in microseconds or nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HumanDuration(pub Duration);
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let duration = self.0;
+        if duration < Duration::from_millis(1) {
+            return match duration.as_nanos() {
+                0 => f.write_str("0s"),
+                nanos @ ..1_000 => write!(f, "{nanos}ns"),
+                nanos => write!(f, "{}µs", nanos / 1_000),
+            };
+        }
+
+        let seconds = duration.as_secs();
+        let units = [
+            (seconds / 3_600, "h"),
+            (seconds / 60 % 60, "m"),
+            (seconds % 60, "s"),
+            (u64::from(duration.subsec_millis()), "ms"),
+        ];
+
+        let mut units = units.into_iter().filter(|(value, _)| *value > 0);
+        if let Some((value, unit)) = units.next() {
+            write!(f, "{value}{unit}")?;
+        }
+        for (value, unit) in units {
+            write!(f, " {value}{unit}")?;
+        }
+        Ok(())
+    }
+}
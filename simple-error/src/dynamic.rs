@@ -0,0 +1,56 @@
+use std::{collections::BTreeMap, error::Error, fmt};
+
+use crate::{parse_internal, Delimiters, Segment};
+
+/// An error whose message is interpolated at runtime, from a format string and values
+/// given by name, e.g. `DynError::new("{a}-{b}").with("a", 1).with("b", 2)`.
+///
+/// Values are displayed as-is, ignoring the format spec of their placeholder. A placeholder
+/// without a value is displayed as written, e.g. `{missing}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DynError {
+    template: String,
+    values: BTreeMap<String, String>,
+}
+
+impl DynError {
+    /// An error displaying the format string, before any value is given.
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Give the value displayed by the placeholders of the given name.
+    #[must_use]
+    pub fn with(mut self, name: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.values.insert(name.into(), value.to_string());
+        self
+    }
+
+    /// The format string the message is interpolated from.
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+}
+
+impl fmt::Display for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (_, segments, _) = parse_internal(&self.template, Delimiters::default(), &[]);
+        for segment in segments {
+            match segment {
+                Segment::Literal(text) => f.write_str(&text)?,
+                Segment::Placeholder(placeholder) => {
+                    match self.values.get(&placeholder.identifier) {
+                        Some(value) if placeholder.members.is_empty() => f.write_str(value)?,
+                        _ => f.write_str(&self.template[placeholder.start..placeholder.end])?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for DynError {}
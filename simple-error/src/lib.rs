@@ -1,18 +1,31 @@
-use std::collections::BTreeSet;
+mod chain;
+mod context;
+mod duration;
+mod dynamic;
+mod memoized;
+mod severity;
 
-#[cfg(feature = "display")]
-use proc_macro2::Ident;
+use std::collections::BTreeSet;
 
 #[cfg(feature = "display")]
 use quote::quote;
 
-use syn::Variant;
+use syn::{Attribute, Expr, Field, Ident, Member, Path, Variant};
+
+pub use chain::Chain;
+pub use context::{Context, WithContext};
+pub use duration::HumanDuration;
+pub use dynamic::DynError;
+pub use memoized::Memoized;
+pub use severity::Severity;
 
 /// The struct that holds the interpolated format string and
 /// the fields used in the format string.
 ///
 /// The default implementation of `ToTokens` is used to generate
-/// the match arms for the `Display` trait implementation.
+/// the match arms for the `Display` trait implementation. Its output only
+/// depends on the input: fields are bound in declaration order, and
+/// arguments are passed in order of appearance in the format string.
 ///
 /// You can also use the fields exposed on the struct to generate
 /// your own match arms for any other trait implementation.
@@ -29,80 +42,888 @@ pub struct Interpolate<'a> {
 
     /// Identifiers used in the interpolated text.
     pub identifiers: BTreeSet<String>,
+
+    /// Every placeholder of the format string, in order of appearance.
+    pub placeholders: Vec<Placeholder>,
+
+    /// The crate the generated code calls `write!` from.
+    pub krate: Crate,
+
+    /// Expressions passed explicitly as positional arguments. When given, positional
+    /// values refer to these rather than to the fields of the variant.
+    pub positional_args: Vec<Expr>,
+
+    /// Names standing for a field of the variant, e.g. `source` for its `#[source]` field.
+    pub aliases: Vec<(String, Member)>,
+
+    /// The wrapper types displaying the values of custom specs, by name of the spec.
+    pub specs: Vec<(String, Path)>,
+
+    /// The problems found in the format string, in order of appearance. The generated
+    /// code reports the first one, if any, through `compile_error!`.
+    pub errors: Vec<ParseError>,
+
+    /// Expressions passed explicitly as named arguments, e.g. `label = self.label()`.
+    /// These take precedence over fields of the same name.
+    pub named_args: Vec<(Ident, Expr)>,
+
+    /// Whether the values formatted by several placeholders are rendered once beforehand,
+    /// rather than by each of them.
+    pub dedup_args: bool,
+
+    /// Whether `Debug` placeholders are pretty-printed, e.g. `{0:?}` as `{0:#?}`, when the
+    /// error itself is displayed in alternate mode, e.g. with `{:#}`.
+    pub propagate_alternate: bool,
+
+    /// The names the fields of a tuple variant are bound to by index, e.g. `path` for its
+    /// first field, rather than the default `__self_0`, for the generated code to read better.
+    pub binding_names: Vec<(usize, Ident)>,
+
+    /// The format string broken into literal text and placeholders.
+    segments: Vec<Segment>,
+
+    /// The format string as written, and its delimiters, to parse it again once
+    /// custom specs are registered.
+    source: (String, Delimiters),
 }
 
-impl Interpolate<'_> {
+/// A part of the format string, either literal text or a placeholder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment {
+    /// Literal text, with escaped delimiters (e.g. `{{`) unescaped.
+    Literal(String),
+
+    /// A placeholder to be interpolated.
+    Placeholder(Placeholder),
+}
+
+/// The standard library crate generated code refers to, e.g. for `::std::write!`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Crate {
+    /// `::std`, available to most crates.
+    #[default]
+    Std,
+
+    /// `::core`, for `#![no_std]` crates.
+    Core,
+}
+
+/// A placeholder of the format string, e.g. `{name:?}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placeholder {
+    /// Identifier of the interpolated value, e.g. `name`, or `__0` for positional values.
+    pub identifier: String,
+
+    /// The format spec following the `:`, if any, e.g. `?` or `#x`.
+    pub spec: Option<String>,
+
+    /// The fields accessed on the value, in order, e.g. `inner` and `code` for `{0.inner.code}`,
+    /// or the methods called on it without arguments, e.g. `as_u16()` for `{status.as_u16()}`.
+    pub members: Vec<String>,
+
+    /// Whether the spec is a custom spec registered through [`Interpolate::with_specs`],
+    /// e.g. `hex` in `{0:hex}`.
+    pub custom: bool,
+
+    /// The byte offset of the opening delimiter in the format string.
+    pub start: usize,
+
+    /// The byte offset just past the closing delimiter in the format string.
+    pub end: usize,
+}
+
+/// A formatting applied by the derive itself rather than by `write!`, written
+/// in place of a format spec, e.g. `{0:path}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PseudoSpec {
+    /// `{0:path}`: displays a `Path`/`PathBuf` through `Path::display`.
+    Path,
+
+    /// `{0:join(, )}`: displays every item of a collection, separated by the given text.
+    Join(String),
+
+    /// `{0:yesno}`: displays a `bool` as `yes` or `no`.
+    YesNo,
+
+    /// `{0:duration}`: displays a `Duration` through [`HumanDuration`], e.g. `5s 200ms`.
+    Duration,
+
+    /// A custom spec, displaying the value through the wrapper type registered for it.
+    Custom(String),
+}
+
+impl PseudoSpec {
+    /// Recognize the pseudo-spec written in place of a format spec.
+    pub fn from_spec(spec: &str) -> Option<Self> {
+        if let Some(separator) = spec.strip_prefix("join(").and_then(|s| s.strip_suffix(')')) {
+            return Some(Self::Join(separator.to_string()));
+        }
+
+        match spec {
+            "path" => Some(Self::Path),
+            "yesno" => Some(Self::YesNo),
+            "duration" => Some(Self::Duration),
+            _ => None,
+        }
+    }
+
+    /// The name of the pseudo-spec, as written in the format string.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Path => "path",
+            Self::Join(_) => "join",
+            Self::YesNo => "yesno",
+            Self::Duration => "duration",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+/// The formatting trait a placeholder formats its value with, according to its format spec.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatTrait {
+    Display,
+    Debug,
+    LowerHex,
+    UpperHex,
+    Octal,
+    Binary,
+    LowerExp,
+    UpperExp,
+    Pointer,
+}
+
+impl Placeholder {
+    /// The formatting trait required by the format spec, or `None` for pseudo-specs,
+    /// as the derive formats those values itself.
+    pub fn format_trait(&self) -> Option<FormatTrait> {
+        if self.pseudo_spec().is_some() {
+            return None;
+        }
+
+        // The trait is selected by the trailing type of the spec, e.g. `x` in `{:#010x}` or `?` in `{:x?}`.
+        let spec = self.spec.as_deref().unwrap_or_default();
+        if spec.ends_with('?') {
+            return Some(FormatTrait::Debug);
+        }
+
+        Some(match spec.chars().last() {
+            Some('x') => FormatTrait::LowerHex,
+            Some('X') => FormatTrait::UpperHex,
+            Some('o') => FormatTrait::Octal,
+            Some('b') => FormatTrait::Binary,
+            Some('e') => FormatTrait::LowerExp,
+            Some('E') => FormatTrait::UpperExp,
+            Some('p') => FormatTrait::Pointer,
+            _ => FormatTrait::Display,
+        })
+    }
+
+    /// The pseudo-spec written in place of the format spec, if any.
+    pub fn pseudo_spec(&self) -> Option<PseudoSpec> {
+        let spec = self.spec.as_deref()?;
+        if self.custom {
+            return Some(PseudoSpec::Custom(spec.to_string()));
+        }
+
+        PseudoSpec::from_spec(spec)
+    }
+
+    /// The arguments the format spec takes its width or precision from, e.g. `__1` in
+    /// `{0:1$}` or `prec` in `{0:.prec$}`.
+    pub fn count_arguments(&self) -> Vec<String> {
+        if self.pseudo_spec().is_some() {
+            return vec![];
+        }
+
+        let spec = self.spec.as_deref().unwrap_or_default();
+        spec_counts(spec)
+            .into_iter()
+            .map(|(start, end)| {
+                let count = &spec[start..end];
+                match count.parse::<usize>() {
+                    Ok(_) => format!("__{count}"),
+                    Err(_) => count.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    /// The format spec as written in the rewritten text, with positional counts renamed
+    /// like positional values, e.g. `1$` to `__1$`.
+    fn rewritten_spec(&self) -> Option<String> {
+        let spec = self.spec.as_deref()?;
+        let mut rewritten = spec.to_string();
+        for (start, end) in spec_counts(spec).into_iter().rev() {
+            if spec[start..end].parse::<usize>().is_ok() {
+                rewritten.insert_str(start, "__");
+            }
+        }
+        Some(rewritten)
+    }
+
+    /// Whether the placeholder refers to a raw identifier, e.g. `{r#type}`, which can't be
+    /// captured by the format string.
+    pub fn is_raw(&self) -> bool {
+        self.identifier.starts_with("r#")
+    }
+
+    /// Whether the placeholder refers to a path rather than a field, e.g. `{Self::PREFIX}`.
+    pub fn is_path(&self) -> bool {
+        self.identifier.contains("::")
+    }
+
+    /// The name of the argument in the rewritten text. Values formatted through a pseudo-spec
+    /// are passed as a separate argument e.g. `__path_0`, as the same value may also be
+    /// formatted as-is elsewhere in the format string. Paths and raw identifiers, which can't
+    /// be captured by the format string, are passed as an argument too, e.g. `__Self__PREFIX`
+    /// or `__r_type`, as are nested fields, e.g. `__0_code` for `{0.code}`.
+    pub fn argument(&self) -> String {
+        let members = self
+            .members
+            .iter()
+            .map(|member| format!("_{}", sanitize(member)))
+            .collect::<String>();
+        match self.pseudo_spec() {
+            Some(pseudo) => format!(
+                "__{}_{}{members}",
+                pseudo.name(),
+                sanitize(self.identifier.trim_start_matches("__"))
+            ),
+            None if self.is_path() || self.is_raw() || !self.members.is_empty() => format!(
+                "__{}{members}",
+                sanitize(self.identifier.trim_start_matches("__"))
+            ),
+            None => self.identifier.clone(),
+        }
+    }
+}
+
+/// The spec with the `#` flag, which follows the fill, alignment and sign, e.g. `>+#8?`
+/// for `>+8?`.
+fn alternate_spec(spec: &str) -> String {
+    let is_align = |c: &char| matches!(c, '<' | '^' | '>');
+    let chars = spec.chars().collect::<Vec<_>>();
+    let mut flag = match chars.as_slice() {
+        [fill, align, ..] if is_align(align) => fill.len_utf8() + 1,
+        [align, ..] if is_align(align) => 1,
+        _ => 0,
+    };
+    if spec[flag..].starts_with(['+', '-']) {
+        flag += 1;
+    }
+
+    let mut alternate = spec.to_string();
+    if !spec[flag..].starts_with('#') {
+        alternate.insert(flag, '#');
+    }
+    alternate
+}
+
+/// The byte ranges of the `name$` and `N$` counts of the format spec, without the `$`.
+fn spec_counts(spec: &str) -> Vec<(usize, usize)> {
+    spec.match_indices('$')
+        .filter_map(|(end, _)| {
+            let start = spec[..end]
+                .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+                .map_or(0, |index| index + 1);
+
+            // A leading `0` is the zero-padding flag, e.g. `{:0width$}`, unless the
+            // count is an index itself.
+            let count = &spec[start..end];
+            let start = match count.parse::<usize>() {
+                Ok(_) => start,
+                Err(_) => end - count.trim_start_matches(|c: char| c.is_ascii_digit()).len(),
+            };
+            (start < end).then_some((start, end))
+        })
+        .collect()
+}
+
+/// Whether the precision of the format spec, if any, is a number e.g. `.3`, or a count
+/// argument e.g. `.prec$` or `.1$`. As values are passed by name, `.*` isn't supported.
+fn valid_precision(spec: &str) -> bool {
+    // A `.` fill is followed by the alignment, e.g. `{:.>8}`.
+    let mut chars = spec.chars();
+    let skip = match (chars.next(), chars.next()) {
+        (Some(fill), Some('<' | '^' | '>')) => fill.len_utf8() + 1,
+        _ => 0,
+    };
+
+    let Some((_, precision)) = spec[skip..].split_once('.') else {
+        return true;
+    };
+
+    let digits = precision.len()
+        - precision
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    if digits > 0 {
+        return true;
+    }
+
+    // Otherwise, a named count argument.
+    let name = precision.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_');
+    name.len() < precision.len() && name.starts_with('$')
+}
+
+/// Replace the characters which aren't allowed in an identifier with `_`.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// A malformed part of a format string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A placeholder is opened but never closed, e.g. the trailing `{` of `"ends with {"`.
+    Unterminated {
+        /// The byte offset of the opening delimiter in the format string.
+        offset: usize,
+
+        /// The delimiters of the format string.
+        delimiters: Delimiters,
+    },
+
+    /// The precision of a format spec is neither a number nor a count argument,
+    /// e.g. `{0:.x}`.
+    InvalidPrecision {
+        /// The byte offset of the opening delimiter of the placeholder in the format string.
+        offset: usize,
+
+        /// The format spec, e.g. `.x`.
+        spec: String,
+    },
+
+    /// A closing brace doesn't close any placeholder, e.g. the `}` of `"a } b"`.
+    UnmatchedClosing {
+        /// The byte offset of the closing brace in the format string.
+        offset: usize,
+    },
+}
+
+impl ParseError {
+    /// The byte offset of the problem in the format string.
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::Unterminated { offset, .. }
+            | Self::InvalidPrecision { offset, .. }
+            | Self::UnmatchedClosing { offset } => *offset,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Unterminated { offset, delimiters } => write!(
+                f,
+                "Unterminated placeholder at offset {offset}, expected a closing `{}`; \
+                 use `{}{}` for a literal `{}`",
+                delimiters.close, delimiters.open, delimiters.open, delimiters.open
+            ),
+            Self::InvalidPrecision { offset, spec } => write!(
+                f,
+                "Invalid precision in the format spec `{spec}` at offset {offset}, expected \
+                 a number e.g. `.3`, or a count argument e.g. `.prec$`"
+            ),
+            Self::UnmatchedClosing { offset } => write!(
+                f,
+                "Unmatched `}}` at offset {offset}; use `}}}}` for a literal `}}`"
+            ),
+        }
+    }
+}
+
+/// The characters opening and closing a placeholder in a format string.
+///
+/// A doubled opening delimiter is an escaped, literal delimiter, e.g. `{{` or `%%`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Delimiters {
+    /// The character opening a placeholder.
+    pub open: char,
+
+    /// The character closing a placeholder.
+    pub close: char,
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Self {
+            open: '{',
+            close: '}',
+        }
+    }
+}
+
+/// The `#[cfg(...)]` attributes of the variant, to be carried over onto any code
+/// generated for it, so that conditionally compiled variants keep compiling.
+pub fn cfg_attrs(variant: &Variant) -> impl Iterator<Item = &Attribute> {
+    variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+}
+
+impl<'a> Interpolate<'a> {
     /// Parse the format text and extract the fields to be interpolated.
     /// Returns a tuple of the fields and the format string with the interpolated
     /// fields replaced with the __ prefix (and for positional values, __0, __1, etc.)
-    pub fn parse<'a>(fmt_text: impl AsRef<str>, variant: &'a Variant) -> Interpolate<'a> {
-        let (rewritten_text, identifiers) = parse_internal(fmt_text);
+    pub fn parse(fmt_text: impl AsRef<str>, variant: &'a Variant) -> Interpolate<'a> {
+        Self::parse_with_delimiters(fmt_text, variant, Delimiters::default())
+    }
 
-        Interpolate {
+    /// Same as [`Interpolate::parse`], but recognizes placeholders enclosed by the given
+    /// delimiters instead of `{...}`, e.g. `%name%`. The rewritten text always uses the
+    /// standard `{...}` syntax, with any literal braces escaped.
+    pub fn parse_with_delimiters(
+        fmt_text: impl AsRef<str>,
+        variant: &'a Variant,
+        delimiters: Delimiters,
+    ) -> Interpolate<'a> {
+        let mut interpolate = Interpolate {
             variant,
-            rewritten_text,
-            identifiers,
+            rewritten_text: String::new(),
+            identifiers: BTreeSet::new(),
+            placeholders: vec![],
+            krate: Crate::default(),
+            aliases: vec![],
+            specs: vec![],
+            errors: vec![],
+            positional_args: vec![],
+            named_args: vec![],
+            dedup_args: false,
+            propagate_alternate: false,
+            binding_names: vec![],
+            segments: vec![],
+            source: (fmt_text.as_ref().to_string(), delimiters),
+        };
+        interpolate.parse_source();
+        interpolate
+    }
+
+    /// Same as [`Interpolate::try_parse`], but for the format string of the literal,
+    /// failing with every problem found as an error spanning the literal, so that they
+    /// can all be reported at once.
+    pub fn parse_collecting(
+        literal: &syn::LitStr,
+        variant: &'a Variant,
+    ) -> Result<Interpolate<'a>, Vec<syn::Error>> {
+        Self::try_parse(literal.value(), variant).map_err(|errors| {
+            errors
+                .iter()
+                .map(|error| syn::Error::new(literal.span(), error))
+                .collect()
+        })
+    }
+
+    /// Same as [`Interpolate::parse`], but fails with every problem found in the format
+    /// string, each along with its byte offset, e.g. for editor integrations. The
+    /// placeholders of the result carry their byte range in the format string.
+    pub fn try_parse(
+        fmt_text: impl AsRef<str>,
+        variant: &'a Variant,
+    ) -> Result<Interpolate<'a>, Vec<ParseError>> {
+        let interpolate = Self::parse(fmt_text, variant);
+        if !interpolate.errors.is_empty() {
+            return Err(interpolate.errors);
+        }
+
+        Ok(interpolate)
+    }
+
+    /// Let placeholders refer to a field by another name, e.g. `{source}` for the field
+    /// returned by `Error::source()`. Like fields, aliases are overridden by explicit arguments.
+    pub fn with_aliases(mut self, aliases: Vec<(String, Member)>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// The field the placeholder refers to through an alias, if any.
+    pub fn alias(&self, placeholder: &Placeholder) -> Option<&Member> {
+        if self.argument_expr(placeholder).is_some() {
+            return None;
+        }
+
+        self.aliases
+            .iter()
+            .find(|(name, _)| *name == placeholder.identifier)
+            .map(|(_, member)| member)
+    }
+
+    /// Register custom specs, displaying the values of the placeholders using them
+    /// through the given wrapper type, e.g. `{0:hex}` as `HexFmt(&field)`.
+    pub fn with_specs(mut self, specs: Vec<(String, Path)>) -> Self {
+        self.specs = specs;
+        self.parse_source();
+        self
+    }
+
+    /// Parse the format string, with the custom specs registered so far.
+    fn parse_source(&mut self) {
+        let custom = self
+            .specs
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        let (text, delimiters) = &self.source;
+        let (rewritten_text, segments, errors) = parse_internal(text, *delimiters, &custom);
+        let placeholders = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Placeholder(placeholder) => Some(placeholder.clone()),
+                Segment::Literal(_) => None,
+            })
+            .collect::<Vec<_>>();
+        self.identifiers = placeholders
+            .iter()
+            .flat_map(|placeholder| {
+                std::iter::once(placeholder.identifier.clone()).chain(placeholder.count_arguments())
+            })
+            .collect();
+
+        self.rewritten_text = rewritten_text;
+        self.placeholders = placeholders;
+        self.segments = segments;
+        self.errors = errors;
+    }
+
+    /// Set the expressions explicitly passed as positional and named arguments.
+    pub fn with_args(mut self, positional_args: Vec<Expr>, named_args: Vec<(Ident, Expr)>) -> Self {
+        self.positional_args = positional_args;
+        self.named_args = named_args;
+        self
+    }
+
+    /// The expression explicitly passed as the argument of the placeholder, if any.
+    pub fn argument_expr(&self, placeholder: &Placeholder) -> Option<&Expr> {
+        match positional_index(&placeholder.identifier) {
+            Some(index) if !self.positional_args.is_empty() => self.positional_args.get(index),
+            _ => self
+                .named_args
+                .iter()
+                .find(|(name, _)| *name == placeholder.identifier)
+                .map(|(_, expr)| expr),
+        }
+    }
+
+    /// The format string broken into literal text and placeholders, in order.
+    pub fn segments(&self) -> Vec<Segment> {
+        self.segments.clone()
+    }
+
+    /// The literal text of the format string, with every placeholder removed and
+    /// escaped delimiters unescaped, e.g. `db error: ` for `db error: {source}`.
+    pub fn strip_placeholders(&self) -> String {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Literal(text) => Some(text.as_str()),
+                Segment::Placeholder(_) => None,
+            })
+            .collect()
+    }
+
+    /// Render the values formatted by several plain placeholders once, into a `String`
+    /// passed to each of them, e.g. for values whose `Display` is expensive.
+    pub fn with_dedup_args(mut self, dedup_args: bool) -> Self {
+        self.dedup_args = dedup_args;
+        self
+    }
+
+    /// Pretty-print the values of `Debug` placeholders when the error is displayed in
+    /// alternate mode, e.g. `{0:?}` as `{0:#?}` for `{:#}`.
+    pub fn with_propagate_alternate(mut self, propagate_alternate: bool) -> Self {
+        self.propagate_alternate = propagate_alternate;
+        self
+    }
+
+    /// Bind the fields of a tuple variant to the given names, by index of the field, e.g.
+    /// `(0, path)` to bind the first field to `path` rather than `__self_0`.
+    pub fn with_binding_names(mut self, binding_names: Vec<(usize, Ident)>) -> Self {
+        self.binding_names = binding_names;
+        self
+    }
+
+    /// The rewritten text used in alternate mode with [`Interpolate::with_propagate_alternate`],
+    /// if any placeholder is pretty-printed there, e.g. `{__0:#?}` for `{0:?}`.
+    pub fn alternate_text(&self) -> Option<String> {
+        if !self.propagate_alternate {
+            return None;
+        }
+
+        let (text, delimiters) = &self.source;
+        let (mut alternate, mut end, mut changed) =
+            (String::with_capacity(text.len() + 1), 0, false);
+        for placeholder in &self.placeholders {
+            let Some(spec) = placeholder.spec.as_deref() else {
+                continue;
+            };
+            if placeholder.format_trait() != Some(FormatTrait::Debug) {
+                continue;
+            }
+
+            // The spec is written last, just before the closing delimiter.
+            let spec_start = placeholder.end - delimiters.close.len_utf8() - spec.len();
+            alternate.push_str(&text[end..spec_start]);
+            alternate.push_str(&alternate_spec(spec));
+            end = spec_start + spec.len();
+            changed |= !spec.contains('#');
+        }
+        alternate.push_str(&text[end..]);
+
+        let custom = self
+            .specs
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        changed.then(|| parse_internal(alternate, *delimiters, &custom).0)
+    }
+
+    /// The format string as written, along with the names of its placeholders in order of
+    /// first appearance, e.g. for message catalogs. Positional values are named by their
+    /// index, e.g. `("{0} in {path}", ["0", "path"])`.
+    pub fn catalog_entry(&self) -> (String, Vec<String>) {
+        let mut names = vec![];
+        for placeholder in &self.placeholders {
+            let identifier = match positional_index(&placeholder.identifier) {
+                Some(index) => index.to_string(),
+                None => placeholder.identifier.clone(),
+            };
+            let name = std::iter::once(identifier)
+                .chain(placeholder.members.iter().cloned())
+                .collect::<Vec<_>>()
+                .join(".");
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        (self.source.0.clone(), names)
+    }
+
+    /// Set the crate the generated code calls `write!` from.
+    pub fn with_crate(mut self, krate: Crate) -> Self {
+        self.krate = krate;
+        self
+    }
+
+    /// The field of the variant the placeholder interpolates, if any.
+    pub fn field(&self, placeholder: &Placeholder) -> Option<&'a Field> {
+        match self.alias(placeholder) {
+            Some(Member::Unnamed(index)) => {
+                return self.variant.fields.iter().nth(index.index as usize);
+            }
+            Some(Member::Named(ident)) => {
+                return self
+                    .variant
+                    .fields
+                    .iter()
+                    .find(|f| f.ident.as_ref() == Some(ident));
+            }
+            None => {}
+        }
+
+        let is_positional = positional_index(&placeholder.identifier).is_some();
+        if self.argument_expr(placeholder).is_some()
+            || (is_positional && !self.positional_args.is_empty())
+        {
+            return None;
+        }
+
+        match &self.variant.fields {
+            syn::Fields::Unnamed(fields) => fields
+                .unnamed
+                .iter()
+                .nth(positional_index(&placeholder.identifier)?),
+            syn::Fields::Named(fields) => fields.named.iter().find(|field| {
+                field
+                    .ident
+                    .as_ref()
+                    .is_some_and(|ident| *ident == placeholder.identifier)
+            }),
+            syn::Fields::Unit => None,
         }
     }
+
+    /// Indices of the positional values (`{}`, `{0}`, etc.) used in the format string,
+    /// in ascending order.
+    pub fn positional_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        // Sorted numerically, as the identifiers sort `__10` before `__2`.
+        self.identifiers
+            .iter()
+            .filter_map(|identifier| positional_index(identifier))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+    }
 }
 
-/// Parse the text and extract the identifiers to be interpolated.
-fn parse_internal(text: impl AsRef<str>) -> (String, BTreeSet<String>) {
-    let mut chars = text.as_ref().chars().peekable();
-    let (mut identifers, mut text, mut positional_index) = (BTreeSet::new(), String::new(), -1);
+/// Parse the text with placeholders enclosed by `delimiters`, and split it into
+/// literal text and the placeholders to be interpolated, along with any problems found.
+fn parse_internal(
+    text: impl AsRef<str>,
+    delimiters: Delimiters,
+    custom: &[&str],
+) -> (String, Vec<Segment>, Vec<ParseError>) {
+    let Delimiters { open, close } = delimiters;
+    let source = text.as_ref();
+    let mut chars = source.chars();
+    let mut errors = vec![];
+    let (mut segments, mut positional_index) = (vec![], -1);
+    let (mut text, mut literal) = (String::with_capacity(source.len()), String::new());
 
-    while let Some(c) = chars.next() {
-        if c != '{' {
+    // The byte offset of the next character, from the characters left to parse.
+    let position = |chars: &std::str::Chars| source.len() - chars.as_str().len();
+
+    // Braces are doubled in the rewritten text to be taken literally by `write!`.
+    let push_escaped = |text: &mut String, c: char| {
+        if c == '{' || c == '}' {
             text.push(c);
+        }
+        text.push(c);
+    };
+
+    while let Some(c) = chars.next() {
+        if c != open {
+            // With custom delimiters, braces are plain text and need escaping.
+            if delimiters != Delimiters::default() {
+                push_escaped(&mut text, c);
+            } else if c == '}' && chars.clone().next() == Some('}') {
+                text.push_str("}}");
+                chars.next();
+            } else if c == '}' {
+                errors.push(ParseError::UnmatchedClosing {
+                    offset: position(&chars) - 1,
+                });
+                text.push(c);
+            } else {
+                text.push(c);
+            }
+
+            literal.push(c);
             continue;
         }
 
-        // If the next character is also a '{', then it's an escaped '{'
-        if let Some('{') = chars.peek() {
-            text.push_str("{{");
+        // If the next character is also an opening delimiter, then it's an escaped one.
+        if chars.clone().next() == Some(open) {
+            push_escaped(&mut text, open);
+            literal.push(open);
             chars.next();
             continue;
         }
 
-        let (mut identifier, mut traits) = ("".to_string(), None);
+        let offset = position(&chars) - open.len_utf8();
+        let (mut identifier, mut traits, mut terminated) = ("".to_string(), None, false);
         while let Some(c) = chars.next() {
+            // A `::` followed by an identifier is part of a path e.g. `{Self::PREFIX}`, rather
+            // than the start of a spec filled with `:` e.g. `{0::>5}`.
+            let mut ahead = chars.clone();
+            let continues_path = ahead.next() == Some(':')
+                && ahead.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                && !identifier.is_empty()
+                && identifier.parse::<usize>().is_err();
+            if c == ':' && continues_path && traits.is_none() {
+                identifier.push_str("::");
+                chars.next();
+                continue;
+            }
+
             if c == ':' {
-                // Collect everything after the ':' as the trait name until we find the closing '}'.
-                while let Some(c) = chars.peek() {
-                    if *c == '}' {
+                // Collect everything after the ':' as the trait name until we find the closing delimiter.
+                // Nothing is collected for an empty spec e.g. `{0:}`, which is then left out.
+                while let Some(c) = chars.clone().next() {
+                    if c == close {
                         break;
                     }
 
-                    traits.get_or_insert("".to_string()).push(*c);
+                    traits.get_or_insert("".to_string()).push(c);
                     chars.next();
                 }
 
                 continue;
             }
 
-            if c == '}' {
+            if c == close {
+                // Fields accessed on the value follow it, e.g. `{0.code}` or `{state.inner.code}`.
+                let mut members = identifier
+                    .split('.')
+                    .map(str::to_string)
+                    .collect::<Vec<_>>();
+                identifier = members.remove(0);
+
+                // `{self.a}` refers to the field of the variant, and is bound the same way as `{a}`.
+                if identifier == "self" && members.first().is_some_and(|member| !member.is_empty())
+                {
+                    identifier = members.remove(0);
+                }
+
                 // If no field name was parsed bfore the ':', then it's a positional value;
                 // so we need to add the index to the field name
                 if identifier.is_empty() {
                     positional_index += 1;
-                    identifier.push_str(&format!("__{}", positional_index));
+                    identifier = format!("__{positional_index}");
                 }
 
-                if identifier.parse::<u8>().is_ok() {
+                if identifier.parse::<usize>().is_ok() {
                     identifier = format!("__{}", identifier);
                 }
 
-                let traits = traits.as_ref().map(|c| format!(":{c}")).unwrap_or_default();
-                text.push_str(&format!("{{{}{}}}", &identifier, traits));
-                identifers.insert(identifier.clone());
+                let custom = traits.as_deref().is_some_and(|spec| custom.contains(&spec));
+                let mut placeholder = Placeholder {
+                    identifier,
+                    spec: traits,
+                    members,
+                    custom,
+                    start: offset,
+                    end: position(&chars),
+                };
+
+                // Left for a mangled spec not to be reported by `write!` as something else.
+                let spec = placeholder.spec.clone().unwrap_or_default();
+                if placeholder.pseudo_spec().is_none() && !valid_precision(&spec) {
+                    errors.push(ParseError::InvalidPrecision { offset, spec });
+                    placeholder.spec = None;
+                }
+
+                // Pseudo-specs are applied to the argument, so they don't end up in the text.
+                text.push('{');
+                text.push_str(&placeholder.argument());
+                if let Some(spec) = placeholder
+                    .rewritten_spec()
+                    .filter(|_| placeholder.pseudo_spec().is_none())
+                {
+                    text.push(':');
+                    text.push_str(&spec);
+                }
+                text.push('}');
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Placeholder(placeholder));
+                terminated = true;
                 break;
             }
 
             identifier.push(c);
         }
+
+        if !terminated {
+            errors.push(ParseError::Unterminated { offset, delimiters });
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
     }
 
-    (text, identifers)
+    (text, segments, errors)
 }
 
 #[cfg(feature = "display")]
@@ -110,92 +931,530 @@ impl quote::ToTokens for Interpolate<'_> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let variant_name = &self.variant.ident;
         let interpolated_text = &self.rewritten_text;
+        let cfg_attrs = cfg_attrs(self.variant);
+        let krate = &self.krate;
 
-        let mappings = match &self.variant.fields {
-            syn::Fields::Unit => {
-                quote! {
-                    Self::#variant_name => write!(f, #interpolated_text),
-                }
-            }
-            syn::Fields::Unnamed(fields) => {
-                let fields = fields.unnamed.iter().collect::<Vec<_>>();
-                let assignments = fields.iter().flat_map(|field| {
-                    field
-                        .ident
-                        .as_ref()
-                        .and_then(|ident| build_ident_assignment(ident, &self.identifiers))
-                });
+        let assignments = self.assignments();
+        let indices = self.field_indices();
 
-                let fields_ident = self
-                    .identifiers
-                    .iter()
-                    .map(|ident| Ident::new(ident, proc_macro2::Span::call_site()));
+        // Variants take precedence over associated items in `Self::` paths, so the patterns
+        // stay unambiguous even if a method or constant shares the name of a variant.
+        let pattern = match &self.variant.fields {
+            syn::Fields::Unit => quote! { Self::#variant_name },
+            syn::Fields::Unnamed(_) => {
+                let bindings = self.positional_bindings();
+                let arity = bindings.last().map_or(0, |(index, _)| index + 1);
 
-                quote! {
-                    Self::#variant_name(#(#fields_ident,)* ..) => write!(f, #interpolated_text, #(#assignments),*),
-                }
+                // Bind every field up to the last referenced one by position, so that
+                // `{1}` always binds the second field regardless of which others are used.
+                let patterns = (0..arity).map(|index| {
+                    match bindings.iter().find(|(bound, _)| *bound == index) {
+                        Some((_, binding)) => quote! { #binding },
+                        None => quote! { _ },
+                    }
+                });
+
+                quote! { Self::#variant_name(#(#patterns,)* ..) }
+            }
+            syn::Fields::Named(_) => {
+                let fields_ident = self.named_bindings();
+                quote! { Self::#variant_name { #(#fields_ident,)* .. } }
             }
-            syn::Fields::Named(fields) => {
-                let fields = fields.named.iter().collect::<Vec<_>>();
-                let fields_ident = fields.iter().flat_map(|field| &field.ident);
+        };
 
-                quote! {
-                    Self::#variant_name { #(#fields_ident,)* } => write!(f, #interpolated_text),
+        // Guards against the pattern eliding a field the format string still refers to.
+        let body = match self.unbound_identifier(&indices) {
+            _ if !self.errors.is_empty() => {
+                let message = self.errors[0].to_string();
+                quote! { ::core::compile_error!(#message) }
+            }
+            Some(identifier) => {
+                let message = format!(
+                    "simple_error: placeholder `{identifier}` of `{variant_name}` has no binding"
+                );
+                quote! { ::core::compile_error!(#message) }
+            }
+            // Nothing is allowed on the `write!` call, so that it still rejects any mismatch
+            // between the text and the arguments, should the rewriting above get it wrong.
+            None => {
+                let mut write = quote! { #krate::write!(f, #interpolated_text, #(#assignments),*) };
+                if let Some(alternate) = self.alternate_text() {
+                    write = quote! {
+                        if f.alternate() {
+                            #krate::write!(f, #alternate, #(#assignments),*)
+                        } else {
+                            #write
+                        }
+                    };
+                }
+                match self.renders() {
+                    renders if renders.is_empty() => write,
+                    renders => quote! {{ #(#renders)* #write }},
                 }
             }
         };
 
-        tokens.extend(mappings);
+        tokens.extend(quote! {
+            #(#cfg_attrs)*
+            #pattern => #body,
+        });
     }
 }
 
 #[cfg(feature = "display")]
-/// Build the assignment for the field if it is used in the format string.
-fn build_ident_assignment(
-    ident: &Ident,
-    used_fields: &BTreeSet<String>,
-) -> Option<proc_macro2::TokenStream> {
-    use quote::format_ident;
-
-    // If the field is not present in the format string, then we don't need to interpolate it
-    if !used_fields.contains(&ident.to_string()) {
-        return None;
+impl Interpolate<'_> {
+    /// The fields of the variant bound by the generated match arm, i.e. those the format
+    /// string or its arguments refer to, in declaration order.
+    pub fn bound_fields(&self) -> Vec<Member> {
+        match &self.variant.fields {
+            syn::Fields::Unnamed(_) => self
+                .field_indices()
+                .into_iter()
+                .map(|index| Member::Unnamed(index.into()))
+                .collect(),
+            syn::Fields::Named(_) => self
+                .named_bindings()
+                .into_iter()
+                .map(|ident| Member::Named(ident.clone()))
+                .collect(),
+            syn::Fields::Unit => vec![],
+        }
     }
 
-    let ident = format_ident!("__{}", ident);
-    Some(quote! { #ident = self.#ident })
-}
+    /// The index of each field of a tuple variant bound by the generated match arm, along
+    /// with the identifier it's bound to, e.g. `(1, __self_1)`. Empty for other variants.
+    pub fn positional_bindings(&self) -> Vec<(usize, Ident)> {
+        match &self.variant.fields {
+            syn::Fields::Unnamed(_) => self
+                .field_indices()
+                .into_iter()
+                .map(|index| (index, self.binding(index)))
+                .collect(),
+            _ => vec![],
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::collections::BTreeSet;
+    /// The identifier the field of a tuple variant is bound to, e.g. `__self_0`.
+    fn binding(&self, index: usize) -> Ident {
+        match self.binding_names.iter().find(|(bound, _)| *bound == index) {
+            Some((_, name)) => {
+                let mut name = name.clone();
+                name.set_span(proc_macro2::Span::mixed_site());
+                name
+            }
+            None => positional_binding(index),
+        }
+    }
 
-    use crate::parse_internal;
+    /// The explicit `name = value` arguments passed to `write!`. Named fields are
+    /// captured by the format string directly, so only positional fields and values
+    /// formatted through a pseudo-spec need one.
+    fn assignments(&self) -> Vec<proc_macro2::TokenStream> {
+        let (mut arguments, rendered) = (BTreeSet::new(), self.rendered_once());
+        self.argument_placeholders()
+            .iter()
+            .filter(|placeholder| arguments.insert(placeholder.argument()))
+            .flat_map(|placeholder| {
+                if !rendered.contains(&placeholder.argument()) {
+                    return build_ident_assignment(placeholder, self);
+                }
 
-    fn to_set<T: ToString>(values: &[T]) -> BTreeSet<String> {
-        values.iter().map(|a| a.to_string()).collect()
+                let ident = Ident::new(&placeholder.argument(), proc_macro2::Span::call_site());
+                let local = rendered_binding(placeholder);
+                Some(quote! { #ident = #local })
+            })
+            .collect()
     }
 
-    #[test]
-    fn test_parse_fmt_string() {
-        assert_eq!(
-            parse_internal("Hello, {name}!"),
-            ("Hello, {name}!".to_string(), to_set(&["name"]))
-        );
+    /// The arguments whose value is rendered once for all of their placeholders: those
+    /// formatted by several placeholders, all displaying the value without a spec, which a
+    /// `String` would display the same way. A spec, e.g. `{0:+}` or `{0:>9}`, applies to the
+    /// value itself, rather than to its rendered `String`.
+    fn rendered_once(&self) -> BTreeSet<String> {
+        if !self.dedup_args {
+            return BTreeSet::new();
+        }
 
-        assert_eq!(
-            parse_internal("Hello, {name}! {age}"),
-            ("Hello, {name}! {age}".to_string(), to_set(&["name", "age"]),)
-        );
+        let is_plain = |placeholder: &Placeholder| {
+            placeholder.format_trait() == Some(FormatTrait::Display)
+                && placeholder.spec.as_deref().unwrap_or_default().is_empty()
+        };
+        let counts = self
+            .placeholders
+            .iter()
+            .flat_map(Placeholder::count_arguments)
+            .collect::<BTreeSet<_>>();
 
-        assert_eq!(
-            parse_internal("Hello, {0}! {1}"),
-            ("Hello, {__0}! {__1}".to_string(), to_set(&["__0", "__1"]),)
-        );
+        let arguments = self
+            .placeholders
+            .iter()
+            .map(Placeholder::argument)
+            .collect::<BTreeSet<_>>();
+        arguments
+            .into_iter()
+            .filter(|argument| !counts.contains(argument))
+            .filter(|argument| {
+                let mut placeholders = self
+                    .placeholders
+                    .iter()
+                    .filter(|placeholder| placeholder.argument() == *argument);
+                placeholders.clone().count() > 1 && placeholders.all(is_plain)
+            })
+            .collect()
+    }
 
-        assert_eq!(
-            parse_internal("Hello, {}! {}"),
-            ("Hello, {__0}! {__1}".to_string(), to_set(&["__0", "__1"]),)
+    /// The statements rendering each value of [`Self::rendered_once`] into a `String`.
+    fn renders(&self) -> Vec<proc_macro2::TokenStream> {
+        let krate = self.krate;
+        self.rendered_once()
+            .iter()
+            .filter_map(|argument| {
+                let placeholder = self
+                    .placeholders
+                    .iter()
+                    .find(|placeholder| placeholder.argument() == *argument)?;
+                let value = argument_value(placeholder, self)?;
+                let local = rendered_binding(placeholder);
+                Some(quote! {
+                    let #local = #krate::string::ToString::to_string(&#value);
+                })
+            })
+            .collect()
+    }
+
+    /// The placeholders, followed by the widths and precisions their specs refer to,
+    /// each as a placeholder of its own.
+    fn argument_placeholders(&self) -> Vec<Placeholder> {
+        let counts = self.placeholders.iter().flat_map(|placeholder| {
+            placeholder
+                .count_arguments()
+                .into_iter()
+                .map(|identifier| Placeholder {
+                    identifier,
+                    spec: None,
+                    members: vec![],
+                    custom: false,
+                    start: placeholder.start,
+                    end: placeholder.end,
+                })
+        });
+
+        self.placeholders.iter().cloned().chain(counts).collect()
+    }
+
+    /// The first positional identifier that neither an explicit argument nor a field
+    /// bound by the match pattern provides a value for.
+    fn unbound_identifier(&self, indices: &BTreeSet<usize>) -> Option<String> {
+        self.argument_placeholders()
+            .into_iter()
+            .filter(|placeholder| self.argument_expr(placeholder).is_none())
+            .find(|placeholder| {
+                let Some(index) = positional_index(&placeholder.identifier) else {
+                    return false;
+                };
+
+                match &self.variant.fields {
+                    syn::Fields::Unnamed(_) => !indices.contains(&index),
+                    syn::Fields::Named(_) => self.field(placeholder).is_none(),
+                    syn::Fields::Unit => true,
+                }
+            })
+            .map(|placeholder| placeholder.identifier)
+    }
+
+    /// The named fields to bind, in declaration order: the fields referred to by the format
+    /// string, or mentioned by any explicit argument.
+    fn named_bindings(&self) -> Vec<&Ident> {
+        // A name following `.` or `::` is a member or path segment, e.g. `code` in
+        // `self.code()`, rather than the field, which would then be bound without being used.
+        fn mentions(tokens: proc_macro2::TokenStream, ident: &Ident) -> bool {
+            let (mut member, mut previous) = (false, None);
+            tokens.into_iter().any(|token| {
+                let found = match &token {
+                    proc_macro2::TokenTree::Ident(other) => other == ident && !member,
+                    proc_macro2::TokenTree::Group(group) => mentions(group.stream(), ident),
+                    _ => false,
+                };
+                let punct = match &token {
+                    proc_macro2::TokenTree::Punct(punct) => Some(punct.as_char()),
+                    _ => None,
+                };
+                member = punct == Some('.') || (previous, punct) == (Some(':'), Some(':'));
+                previous = punct;
+                found
+            })
+        }
+
+        let args = self
+            .positional_args
+            .iter()
+            .chain(self.named_args.iter().map(|(_, expr)| expr))
+            .collect::<Vec<_>>();
+
+        self.variant
+            .fields
+            .iter()
+            .flat_map(|field| &field.ident)
+            .filter(|ident| {
+                self.identifiers.contains(&ident.to_string())
+                    || args.iter().any(|arg| mentions(quote! { #arg }, ident))
+                    || self
+                        .aliased()
+                        .any(|member| *member == Member::Named((*ident).clone()))
+            })
+            .collect()
+    }
+
+    /// The fields referred to through an alias by any placeholder.
+    fn aliased(&self) -> impl Iterator<Item = &Member> + '_ {
+        self.placeholders
+            .iter()
+            .filter_map(|placeholder| self.alias(placeholder))
+    }
+
+    /// Indices of the tuple fields to bind, as referenced by positional values.
+    fn field_indices(&self) -> BTreeSet<usize> {
+        let aliased = self.aliased().filter_map(|member| match member {
+            Member::Unnamed(index) => Some(index.index as usize),
+            Member::Named(_) => None,
+        });
+
+        if !self.positional_args.is_empty() {
+            return aliased.collect();
+        }
+
+        self.positional_indices().chain(aliased).collect()
+    }
+}
+
+#[cfg(feature = "display")]
+impl quote::ToTokens for Crate {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(match self {
+            Self::Std => quote! { ::std },
+            Self::Core => quote! { ::core },
+        });
+    }
+}
+
+#[cfg(feature = "display")]
+impl quote::ToTokens for FormatTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        // `std::fmt` re-exports `core::fmt`, so this path fits either crate.
+        let ident = Ident::new(&format!("{self:?}"), proc_macro2::Span::call_site());
+        tokens.extend(quote! { ::core::fmt::#ident });
+    }
+}
+
+#[cfg(feature = "display")]
+impl PseudoSpec {
+    /// Apply the pseudo-spec to the tokens of the formatted value, wrapping the value of
+    /// a custom spec with the type it's registered with.
+    fn expand(
+        &self,
+        value: proc_macro2::TokenStream,
+        specs: &[(String, Path)],
+        krate: Crate,
+    ) -> proc_macro2::TokenStream {
+        match self {
+            Self::Path => quote! { #value.display() },
+            Self::Join(separator) => quote! {{
+                struct Join<I>(I, &'static str);
+
+                impl<I> #krate::fmt::Display for Join<I>
+                where
+                    I: #krate::clone::Clone + #krate::iter::IntoIterator,
+                    I::Item: #krate::fmt::Display,
+                {
+                    fn fmt(&self, f: &mut #krate::fmt::Formatter) -> #krate::fmt::Result {
+                        let items = #krate::iter::IntoIterator::into_iter(#krate::clone::Clone::clone(&self.0));
+                        for (index, item) in #krate::iter::Iterator::enumerate(items) {
+                            if index > 0 {
+                                f.write_str(self.1)?;
+                            }
+                            #krate::fmt::Display::fmt(&item, f)?;
+                        }
+                        #krate::result::Result::Ok(())
+                    }
+                }
+
+                Join(#value, #separator)
+            }},
+            // Literal patterns see through the reference the field is bound as.
+            Self::YesNo => quote! {
+                match #value {
+                    true => "yes",
+                    false => "no",
+                }
+            },
+            // Borrowed, as the field is bound as a reference, but the argument may not be.
+            Self::Duration => quote! {
+                ::simple_error::HumanDuration(*#krate::borrow::Borrow::<
+                    #krate::time::Duration,
+                >::borrow(&#value))
+            },
+            Self::Custom(name) => match specs.iter().find(|(spec, _)| spec == name) {
+                Some((_, wrapper)) => quote! { #wrapper(&#value) },
+                None => value,
+            },
+        }
+    }
+}
+
+/// Extract the index from a rewritten positional identifier, i.e. `__0` yields `0`.
+fn positional_index(identifier: &str) -> Option<usize> {
+    identifier.strip_prefix("__")?.parse().ok()
+}
+
+#[cfg(feature = "display")]
+/// The pattern binding for the positional field at `index`.
+///
+/// The binding is resolved with mixed-site hygiene, so it can never clash with
+/// an identifier written in the format string.
+fn positional_binding(index: usize) -> Ident {
+    Ident::new(&format!("__self_{index}"), proc_macro2::Span::mixed_site())
+}
+
+#[cfg(feature = "display")]
+/// Build the `argument = value` assignment passed to `write!` for the placeholder, if it needs one.
+fn build_ident_assignment(
+    placeholder: &Placeholder,
+    interpolate: &Interpolate,
+) -> Option<proc_macro2::TokenStream> {
+    let expr = interpolate.argument_expr(placeholder);
+    let index = bound_index(placeholder, interpolate);
+    let value = argument_value(placeholder, interpolate)?;
+    let value = match placeholder.pseudo_spec() {
+        Some(pseudo) => pseudo.expand(value, &interpolate.specs, interpolate.krate),
+        None if expr.is_some()
+            || index.is_some()
+            || placeholder.is_path()
+            || placeholder.is_raw()
+            || !placeholder.members.is_empty()
+            || interpolate.alias(placeholder).is_some() =>
+        {
+            value
+        }
+        // Captured by the format string directly.
+        None => return None,
+    };
+
+    let ident = Ident::new(&placeholder.argument(), proc_macro2::Span::call_site());
+    Some(quote! { #ident = #value })
+}
+
+#[cfg(feature = "display")]
+/// The index of the tuple field the placeholder formats, if bound by the match pattern.
+fn bound_index(placeholder: &Placeholder, interpolate: &Interpolate) -> Option<usize> {
+    positional_index(&placeholder.identifier)
+        .filter(|index| interpolate.field_indices().contains(index))
+        .filter(|_| matches!(interpolate.variant.fields, syn::Fields::Unnamed(_)))
+}
+
+#[cfg(feature = "display")]
+/// The value the placeholder formats, before any pseudo-spec is applied.
+fn argument_value(
+    placeholder: &Placeholder,
+    interpolate: &Interpolate,
+) -> Option<proc_macro2::TokenStream> {
+    let expr = interpolate.argument_expr(placeholder);
+    let value = match (expr, bound_index(placeholder, interpolate)) {
+        (Some(expr), _) => quote! { (#expr) },
+        (None, Some(index)) => {
+            let binding = interpolate.binding(index);
+            quote! { #binding }
+        }
+        (None, None) => match interpolate.alias(placeholder) {
+            Some(Member::Unnamed(index)) => {
+                let binding = interpolate.binding(index.index as usize);
+                quote! { #binding }
+            }
+            Some(Member::Named(ident)) => quote! { #ident },
+            None => {
+                // Anything that isn't a path is left for `write!` to report.
+                let path = syn::parse_str::<syn::Path>(&placeholder.identifier).ok()?;
+                quote! { #path }
+            }
+        },
+    };
+
+    // A member can also be a method called without arguments, e.g. `{status.as_u16()}`.
+    let members = placeholder
+        .members
+        .iter()
+        .map(|member| match member.strip_suffix("()") {
+            Some(method) => {
+                let method = syn::parse_str::<Ident>(method).ok()?;
+                Some(quote! { .#method() })
+            }
+            None => {
+                let member = syn::parse_str::<Member>(member).ok()?;
+                Some(quote! { .#member })
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(quote! { #value #(#members)* })
+}
+
+#[cfg(feature = "display")]
+/// The local holding the value of the placeholder rendered once, see `Interpolate::dedup_args`.
+fn rendered_binding(placeholder: &Placeholder) -> Ident {
+    Ident::new(
+        &format!(
+            "__rendered_{}",
+            placeholder.argument().trim_start_matches("__")
+        ),
+        proc_macro2::Span::mixed_site(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::{Delimiters, FormatTrait, Placeholder, Segment};
+
+    fn to_set<T: ToString>(values: &[T]) -> BTreeSet<String> {
+        values.iter().map(|a| a.to_string()).collect()
+    }
+
+    /// Parse the text with the given delimiters, keeping only the set of identifiers.
+    fn parse_delimited(text: &str, delimiters: Delimiters) -> (String, BTreeSet<String>) {
+        let (text, segments, _) = crate::parse_internal(text, delimiters, &[]);
+        let identifiers = segments
+            .into_iter()
+            .filter_map(|segment| match segment {
+                Segment::Placeholder(placeholder) => Some(placeholder.identifier),
+                Segment::Literal(_) => None,
+            })
+            .collect();
+        (text, identifiers)
+    }
+
+    fn parse_internal(text: &str) -> (String, BTreeSet<String>) {
+        parse_delimited(text, Delimiters::default())
+    }
+
+    #[test]
+    fn test_parse_fmt_string() {
+        assert_eq!(
+            parse_internal("Hello, {name}!"),
+            ("Hello, {name}!".to_string(), to_set(&["name"]))
+        );
+
+        assert_eq!(
+            parse_internal("Hello, {name}! {age}"),
+            ("Hello, {name}! {age}".to_string(), to_set(&["name", "age"]),)
+        );
+
+        assert_eq!(
+            parse_internal("Hello, {0}! {1}"),
+            ("Hello, {__0}! {__1}".to_string(), to_set(&["__0", "__1"]),)
+        );
+
+        assert_eq!(
+            parse_internal("Hello, {}! {}"),
+            ("Hello, {__0}! {__1}".to_string(), to_set(&["__0", "__1"]),)
         );
 
         assert_eq!(
@@ -242,4 +1501,752 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_parse_escaped_braces() {
+        assert_eq!(
+            parse_internal("{{}}"),
+            ("{{}}".to_string(), BTreeSet::new())
+        );
+
+        assert_eq!(
+            parse_internal("Hello, {{}} {name} {{}}"),
+            ("Hello, {{}} {name} {{}}".to_string(), to_set(&["name"]))
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_delimiters() {
+        let percent = Delimiters {
+            open: '%',
+            close: '%',
+        };
+
+        assert_eq!(
+            parse_delimited("Hello, %name%!", percent),
+            ("Hello, {name}!".to_string(), to_set(&["name"]))
+        );
+
+        assert_eq!(
+            parse_delimited("%0:?% is 100%% {done}", percent),
+            ("{__0:?} is 100% {{done}}".to_string(), to_set(&["__0"]))
+        );
+
+        assert_eq!(
+            parse_delimited(
+                "<name> {<0>}",
+                Delimiters {
+                    open: '<',
+                    close: '>',
+                }
+            ),
+            ("{name} {{{__0}}}".to_string(), to_set(&["name", "__0"]))
+        );
+    }
+
+    #[test]
+    fn test_parse_explicit_high_indices() {
+        assert_eq!(
+            parse_internal("{10} {11:?} {} {300}"),
+            (
+                "{__10} {__11:?} {__0} {__300}".to_string(),
+                to_set(&["__10", "__11", "__0", "__300"])
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        let placeholder = |identifier: &str, spec: Option<&str>, start, end| {
+            Segment::Placeholder(Placeholder {
+                identifier: identifier.to_string(),
+                spec: spec.map(str::to_string),
+                members: vec![],
+                custom: false,
+                start,
+                end,
+            })
+        };
+
+        assert_eq!(
+            crate::parse_internal("{{x}} = {name:?}, {}!{0}", Delimiters::default(), &[]).1,
+            vec![
+                Segment::Literal("{x} = ".to_string()),
+                placeholder("name", Some("?"), 8, 16),
+                Segment::Literal(", ".to_string()),
+                placeholder("__0", None, 18, 20),
+                Segment::Literal("!".to_string()),
+                placeholder("__0", None, 21, 24),
+            ]
+        );
+
+        let percent = Delimiters {
+            open: '%',
+            close: '%',
+        };
+        assert_eq!(
+            crate::parse_internal("{100%%} %name%", percent, &[]).1,
+            vec![
+                Segment::Literal("{100%} ".to_string()),
+                placeholder("name", None, 8, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_parse() {
+        let variant: syn::Variant = syn::parse_quote! { Db { source: u8, table: u8 } };
+        let interpolate =
+            crate::Interpolate::try_parse("db: {table} ({source:?}) {{ é {}", &variant).unwrap();
+        let positions = interpolate
+            .placeholders
+            .iter()
+            .map(|placeholder| {
+                (
+                    placeholder.identifier.as_str(),
+                    placeholder.start,
+                    placeholder.end,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            [("table", 4, 11), ("source", 13, 23), ("__0", 31, 33)]
+        );
+
+        let errors = crate::Interpolate::try_parse("{table} {source é {", &variant)
+            .err()
+            .unwrap();
+        let offsets = errors
+            .iter()
+            .map(crate::ParseError::offset)
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, [8]);
+    }
+
+    #[test]
+    fn test_parse_collecting() {
+        let variant: syn::Variant = syn::parse_quote! { Db { table: u8 } };
+        let literal: syn::LitStr = syn::parse_quote!("{table:.x} } {table");
+        let errors = crate::Interpolate::parse_collecting(&literal, &variant)
+            .err()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "Invalid precision in the format spec `.x` at offset 0, expected a number \
+                 e.g. `.3`, or a count argument e.g. `.prec$`",
+                "Unmatched `}` at offset 11; use `}}` for a literal `}`",
+                "Unterminated placeholder at offset 13, expected a closing `}`; use `{{` \
+                 for a literal `{`",
+            ]
+        );
+
+        let literal: syn::LitStr = syn::parse_quote!("{table} }} {{");
+        let interpolate = crate::Interpolate::parse_collecting(&literal, &variant).unwrap();
+        assert_eq!(interpolate.rewritten_text, "{table} }} {{");
+    }
+
+    #[test]
+    fn test_long_format_string() {
+        let chunk = "{name} {{é}} {:>8} {0.code:?} ";
+        let count = 10_000 / chunk.len() + 1;
+        let text = chunk.repeat(count);
+        let (rewritten, segments, errors) =
+            crate::parse_internal(&text, Delimiters::default(), &[]);
+        assert!(errors.is_empty());
+
+        let expected = (0..count)
+            .map(|index| format!("{{name}} {{{{é}}}} {{__{index}:>8}} {{__0_code:?}} "))
+            .collect::<String>();
+        assert!(rewritten == expected, "the rewritten text differs");
+
+        let written = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Placeholder(placeholder) => {
+                    Some(&text[placeholder.start..placeholder.end])
+                }
+                Segment::Literal(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(written == ["{name}", "{:>8}", "{0.code:?}"].repeat(count));
+    }
+
+    #[test]
+    fn test_alternate_text() {
+        assert_eq!(crate::alternate_spec("?"), "#?");
+        assert_eq!(crate::alternate_spec("*^+08x?"), "*^+#08x?");
+        assert_eq!(crate::alternate_spec("é<#?"), "é<#?");
+
+        let variant: syn::Variant = syn::parse_quote! { Pair(u8, u8) };
+        let interpolate = |text| {
+            crate::Interpolate::parse_with_delimiters(
+                text,
+                &variant,
+                Delimiters {
+                    open: '<',
+                    close: '>',
+                },
+            )
+            .with_propagate_alternate(true)
+            .alternate_text()
+        };
+        assert_eq!(
+            interpolate("<0:?> <1> {}").as_deref(),
+            Some("{__0:#?} {__1} {{}}")
+        );
+        assert_eq!(interpolate("<0:#?> <1:x>"), None);
+        assert_eq!(
+            crate::Interpolate::parse("{0:?}", &variant).alternate_text(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_self_field_access() {
+        assert_eq!(
+            parse_internal("{a} / {self.a} {self.b.code} {self.0}"),
+            (
+                "{a} / {a} {__b_code} {__0}".to_string(),
+                to_set(&["a", "b", "__0"])
+            )
+        );
+    }
+
+    #[test]
+    fn test_catalog_entry() {
+        let variant: syn::Variant = syn::parse_quote! { Db { source: u8, table: u8 } };
+        let entry = |text| crate::Interpolate::parse(text, &variant).catalog_entry();
+
+        assert_eq!(
+            entry("db error: {source}"),
+            ("db error: {source}".to_string(), vec!["source".to_string()])
+        );
+        assert_eq!(
+            entry("{table:>8} {{literal}} {} {0:?} {source.code} {table}"),
+            (
+                "{table:>8} {{literal}} {} {0:?} {source.code} {table}".to_string(),
+                vec![
+                    "table".to_string(),
+                    "0".to_string(),
+                    "source.code".to_string()
+                ]
+            )
+        );
+        assert_eq!(entry("Unit error"), ("Unit error".to_string(), vec![]));
+    }
+
+    #[test]
+    fn test_strip_placeholders() {
+        let variant: syn::Variant = syn::parse_quote! { Db { source: u8, table: u8 } };
+        let strip = |text| crate::Interpolate::parse(text, &variant).strip_placeholders();
+
+        assert_eq!(strip("db error: {source}"), "db error: ");
+        assert_eq!(strip("{table}: {source:?} ({})"), ":  ()");
+        assert_eq!(strip("{{table}} {table} }}{{"), "{table}  }{");
+        assert_eq!(strip("{source}{table}"), "");
+    }
+
+    #[test]
+    fn test_parse_paths() {
+        assert_eq!(
+            parse_internal("{Self::PREFIX} {Self::PREFIX:>8} {crate::NAME:?}"),
+            (
+                "{__Self__PREFIX} {__Self__PREFIX:>8} {__crate__NAME:?}".to_string(),
+                to_set(&["Self::PREFIX", "crate::NAME"])
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_trait() {
+        let format_trait = |spec: &str| {
+            let placeholder = Placeholder {
+                identifier: "__0".to_string(),
+                spec: Some(spec.to_string()),
+                members: vec![],
+                custom: false,
+                start: 0,
+                end: 0,
+            };
+            placeholder.format_trait()
+        };
+
+        assert_eq!(format_trait(""), Some(FormatTrait::Display));
+        assert_eq!(format_trait(">10"), Some(FormatTrait::Display));
+        assert_eq!(format_trait("x<5"), Some(FormatTrait::Display));
+        assert_eq!(format_trait("?"), Some(FormatTrait::Debug));
+        assert_eq!(format_trait("#x?"), Some(FormatTrait::Debug));
+        assert_eq!(format_trait("#010x"), Some(FormatTrait::LowerHex));
+        assert_eq!(format_trait("X"), Some(FormatTrait::UpperHex));
+        assert_eq!(format_trait("o"), Some(FormatTrait::Octal));
+        assert_eq!(format_trait("b"), Some(FormatTrait::Binary));
+        assert_eq!(format_trait(".3e"), Some(FormatTrait::LowerExp));
+        assert_eq!(format_trait("E"), Some(FormatTrait::UpperExp));
+        assert_eq!(format_trait("p"), Some(FormatTrait::Pointer));
+        assert_eq!(format_trait("path"), None);
+    }
+
+    #[test]
+    fn test_parse_pseudo_spec() {
+        assert_eq!(
+            parse_internal("{0:path} {0} {name:path}"),
+            (
+                "{__path_0} {__0} {__path_name}".to_string(),
+                to_set(&["__0", "name"])
+            )
+        );
+
+        assert_eq!(
+            crate::parse_internal("{:path}", Delimiters::default(), &[]).1,
+            vec![Segment::Placeholder(Placeholder {
+                identifier: "__0".to_string(),
+                spec: Some("path".to_string()),
+                members: vec![],
+                custom: false,
+                start: 0,
+                end: 7,
+            })]
+        );
+    }
+
+    #[cfg(feature = "display")]
+    #[test]
+    fn test_every_identifier_is_bound() {
+        use quote::ToTokens;
+
+        let variant: syn::Variant = syn::parse_quote! {
+            Wide(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8)
+        };
+        let interpolate = crate::Interpolate::parse("{10} {2}", &variant);
+        let tokens = interpolate.to_token_stream().to_string();
+        assert!(!tokens.contains("compile_error"));
+        assert!(tokens.contains(
+            "Self :: Wide (_ , _ , __self_2 , _ , _ , _ , _ , _ , _ , _ , __self_10 , ..)"
+        ));
+
+        let variant: syn::Variant = syn::parse_quote! { Unit };
+        let interpolate = crate::Interpolate::parse("{0}", &variant);
+        let tokens = interpolate.to_token_stream().to_string();
+        assert!(tokens.contains(
+            "compile_error ! (\"simple_error: placeholder `__0` of `Unit` has no binding\")"
+        ));
+    }
+
+    #[cfg(feature = "display")]
+    #[test]
+    fn test_positional_bindings() {
+        let variant: syn::Variant = syn::parse_quote! { Sparse(u8, u8, u8, u8, u8) };
+        let interpolate = crate::Interpolate::parse("{3} {1} {3:?}", &variant);
+        let bindings = interpolate
+            .positional_bindings()
+            .into_iter()
+            .map(|(index, ident)| (index, ident.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bindings,
+            [(1, "__self_1".to_string()), (3, "__self_3".to_string())]
+        );
+
+        let variant: syn::Variant = syn::parse_quote! { Named { code: u8 } };
+        let interpolate = crate::Interpolate::parse("{code}", &variant);
+        assert!(interpolate.positional_bindings().is_empty());
+    }
+
+    #[cfg(feature = "display")]
+    #[test]
+    fn test_mismatched_state_is_left_to_write() {
+        use quote::ToTokens;
+
+        let variant: syn::Variant = syn::parse_quote! { Pair(u8, u8) };
+        let mut interpolate = crate::Interpolate::parse("{0}", &variant);
+        interpolate.rewritten_text = "{__0} {__1}".to_string();
+        let tokens = interpolate.to_token_stream().to_string();
+        assert_eq!(
+            tokens,
+            r#"Self :: Pair (__self_0 , ..) => :: std :: write ! (f , "{__0} {__1}" , __0 = __self_0) ,"#
+        );
+    }
+
+    #[test]
+    fn test_dyn_error() {
+        use crate::DynError;
+
+        let error = DynError::new("{a}-{b}").with("a", 1).with("b", 2);
+        assert_eq!(error.to_string(), "1-2");
+        assert_eq!(error.template(), "{a}-{b}");
+
+        let error = DynError::new("{{{name:>8}}} {name} {missing} {name.len}").with("name", "x");
+        assert_eq!(error.to_string(), "{x} x {missing} {name.len}");
+
+        let error = DynError::new("{a}").with("a", 1).with("a", "one");
+        assert_eq!(error.to_string(), "one");
+    }
+
+    #[test]
+    fn test_memoized() {
+        use std::{cell::Cell, error::Error, fmt};
+
+        use crate::{Context, Memoized};
+
+        struct Expensive<'a>(&'a Cell<usize>);
+
+        impl fmt::Display for Expensive<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.set(self.0.get() + 1);
+                f.write_str("expensive")
+            }
+        }
+
+        let renders = Cell::new(0);
+        let error = Memoized::new(Expensive(&renders));
+        assert_eq!(renders.get(), 0);
+        assert_eq!(error.to_string(), "expensive");
+        assert_eq!(format!("{error} {error}"), "expensive expensive");
+        assert_eq!(error.message(), "expensive");
+        assert_eq!(renders.get(), 1);
+
+        let error = Memoized::new("x".parse::<i32>().context("parsing").unwrap_err());
+        assert_eq!(error.to_string(), "parsing");
+        assert!(error.source().unwrap().is::<std::num::ParseIntError>());
+        assert_eq!(error.into_inner().context(), "parsing");
+    }
+
+    #[test]
+    fn test_context_chain() {
+        use std::error::Error;
+
+        use crate::{Context, WithContext};
+
+        fn read() -> Result<i32, WithContext<WithContext<std::num::ParseIntError>>> {
+            let value = "x"
+                .parse::<i32>()
+                .with_context(|| format!("parsing `{}`", "x"))
+                .context("reading the config")?;
+            Ok(value)
+        }
+
+        let error = read().unwrap_err();
+        assert_eq!(error.context(), "reading the config");
+
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(error) = source {
+            chain.push(error.to_string());
+            source = error.source();
+        }
+        assert_eq!(
+            chain,
+            [
+                "reading the config",
+                "parsing `x`",
+                "invalid digit found in string"
+            ]
+        );
+
+        let error = error.into_inner();
+        assert_eq!(
+            error.get_ref().kind(),
+            &std::num::IntErrorKind::InvalidDigit
+        );
+        assert!(Ok::<_, std::fmt::Error>(1).context("unused").is_ok());
+    }
+
+    #[test]
+    fn test_parse_count_arguments() {
+        assert_eq!(
+            parse_internal("{0:1$} {:>width$.prec$} {:0w$} {:$>5}"),
+            (
+                "{__0:__1$} {__0:>width$.prec$} {__1:0w$} {__2:$>5}".to_string(),
+                to_set(&["__0", "__1", "__2"])
+            )
+        );
+
+        let placeholder = |spec: &str| Placeholder {
+            identifier: "__0".to_string(),
+            spec: Some(spec.to_string()),
+            members: vec![],
+            custom: false,
+            start: 0,
+            end: 0,
+        };
+        assert_eq!(placeholder("1$").count_arguments(), ["__1"]);
+        assert_eq!(placeholder("^10$.2$").count_arguments(), ["__10", "__2"]);
+        assert_eq!(
+            placeholder(">width$.prec$").count_arguments(),
+            ["width", "prec"]
+        );
+        assert_eq!(placeholder("0w$x").count_arguments(), ["w"]);
+        assert!(placeholder("$>5").count_arguments().is_empty());
+    }
+
+    #[test]
+    fn test_parse_unterminated() {
+        let (_, segments, errors) =
+            crate::parse_internal("ends with {", Delimiters::default(), &[]);
+        assert_eq!(segments, [Segment::Literal("ends with ".to_string())]);
+        assert_eq!(
+            errors,
+            [crate::ParseError::Unterminated {
+                offset: 10,
+                delimiters: Delimiters::default()
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Unterminated placeholder at offset 10, expected a closing `}`; use `{{` for a literal `{`"
+        );
+
+        let percent = Delimiters {
+            open: '%',
+            close: '%',
+        };
+        let (_, _, errors) = crate::parse_internal("é {name:?", Delimiters::default(), &[]);
+        assert!(matches!(
+            errors[..],
+            [crate::ParseError::Unterminated { offset: 3, .. }]
+        ));
+        let (_, _, errors) = crate::parse_internal("100%", percent, &[]);
+        assert!(matches!(
+            errors[..],
+            [crate::ParseError::Unterminated { offset: 3, .. }]
+        ));
+        assert!(
+            crate::parse_internal("ends with {{", Delimiters::default(), &[])
+                .2
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_spec() {
+        let (text, segments, _) =
+            crate::parse_internal("{0:hex} {0:x}", Delimiters::default(), &["hex"]);
+        assert_eq!(text, "{__hex_0} {__0:x}");
+
+        let Segment::Placeholder(placeholder) = &segments[0] else {
+            panic!("expected a placeholder");
+        };
+        assert_eq!(
+            placeholder.pseudo_spec(),
+            Some(crate::PseudoSpec::Custom("hex".to_string()))
+        );
+        assert_eq!(placeholder.format_trait(), None);
+    }
+
+    #[test]
+    fn test_parse_escaped_positional() {
+        assert_eq!(
+            parse_internal("use {{0}} literally"),
+            ("use {{0}} literally".to_string(), to_set::<&str>(&[]))
+        );
+        assert_eq!(
+            parse_internal("{{{0}}} {0}"),
+            ("{{{__0}}} {__0}".to_string(), to_set(&["__0"]))
+        );
+    }
+
+    #[test]
+    fn test_parse_join() {
+        let (text, segments, _) =
+            crate::parse_internal("errors: {0:join(, )}", Delimiters::default(), &[]);
+        assert_eq!(text, "errors: {__join_0}");
+
+        let Segment::Placeholder(placeholder) = &segments[1] else {
+            panic!("expected a placeholder");
+        };
+        assert_eq!(
+            placeholder.pseudo_spec(),
+            Some(crate::PseudoSpec::Join(", ".to_string()))
+        );
+        assert!(placeholder.count_arguments().is_empty());
+    }
+
+    #[test]
+    fn test_parse_yesno() {
+        let (text, segments, _) =
+            crate::parse_internal("retry: {0:yesno} {0}", Delimiters::default(), &[]);
+        assert_eq!(text, "retry: {__yesno_0} {__0}");
+
+        let Segment::Placeholder(placeholder) = &segments[1] else {
+            panic!("expected a placeholder");
+        };
+        assert_eq!(placeholder.pseudo_spec(), Some(crate::PseudoSpec::YesNo));
+        assert_eq!(placeholder.format_trait(), None);
+    }
+
+    #[test]
+    fn test_human_duration() {
+        use std::time::Duration;
+
+        use crate::HumanDuration;
+
+        let display = |duration| HumanDuration(duration).to_string();
+        assert_eq!(display(Duration::ZERO), "0s");
+        assert_eq!(display(Duration::from_nanos(250)), "250ns");
+        assert_eq!(display(Duration::from_micros(42)), "42µs");
+        assert_eq!(display(Duration::from_millis(200)), "200ms");
+        assert_eq!(display(Duration::from_millis(5_200)), "5s 200ms");
+        assert_eq!(display(Duration::from_secs(5)), "5s");
+        assert_eq!(display(Duration::from_secs(5_400)), "1h 30m");
+        assert_eq!(display(Duration::from_secs(3_661)), "1h 1m 1s");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let (text, segments, _) =
+            crate::parse_internal("after {0:duration} ({0:?})", Delimiters::default(), &[]);
+        assert_eq!(text, "after {__duration_0} ({__0:?})");
+
+        let Segment::Placeholder(placeholder) = &segments[1] else {
+            panic!("expected a placeholder");
+        };
+        assert_eq!(placeholder.pseudo_spec(), Some(crate::PseudoSpec::Duration));
+    }
+
+    #[test]
+    fn test_chain() {
+        use crate::{Chain, Context};
+
+        let error = "x".parse::<i32>().context("parsing").unwrap_err();
+        let chain = Chain::new(&error)
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(chain, ["parsing", "invalid digit found in string"]);
+    }
+
+    #[test]
+    fn test_parse_fill_and_align() {
+        assert_eq!(
+            parse_internal("{0:*<10} {0:0>8} {0:^6} {0::>5} {name::<3}"),
+            (
+                "{__0:*<10} {__0:0>8} {__0:^6} {__0::>5} {name::<3}".to_string(),
+                to_set(&["__0", "name"])
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_empty_spec() {
+        assert_eq!(
+            parse_internal("{:} {0:} {:}{:} {name:}"),
+            (
+                "{__0} {__0} {__1}{__2} {name}".to_string(),
+                to_set(&["__0", "__1", "__2", "name"])
+            )
+        );
+
+        let (_, segments, _) = crate::parse_internal("{:}", Delimiters::default(), &[]);
+        assert!(matches!(
+            &segments[..],
+            [Segment::Placeholder(Placeholder { spec: None, .. })]
+        ));
+    }
+
+    #[test]
+    fn test_parse_method_calls() {
+        let (text, segments, errors) = crate::parse_internal(
+            "{status.as_u16()} {status.as_u16} {0.inner.len():>4}",
+            Delimiters::default(),
+            &[],
+        );
+        assert!(errors.is_empty());
+        assert_eq!(
+            text,
+            "{__status_as_u16__} {__status_as_u16} {__0_inner_len__:>4}"
+        );
+        assert!(matches!(
+            &segments[..],
+            [Segment::Placeholder(Placeholder { members, .. }), ..] if members == &["as_u16()"]
+        ));
+    }
+
+    #[test]
+    fn test_parse_nested_fields() {
+        let (text, segments, _) = crate::parse_internal(
+            "{0.code} {0.inner.x:>4} {state.code:?} {0}",
+            Delimiters::default(),
+            &[],
+        );
+        assert_eq!(text, "{__0_code} {__0_inner_x:>4} {__state_code:?} {__0}");
+
+        let members = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Placeholder(placeholder) => {
+                    Some((placeholder.identifier.as_str(), placeholder.members.clone()))
+                }
+                Segment::Literal(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            [
+                ("__0", vec!["code".to_string()]),
+                ("__0", vec!["inner".to_string(), "x".to_string()]),
+                ("state", vec!["code".to_string()]),
+                ("__0", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_placeholder_followed_by_escaped_brace() {
+        assert_eq!(
+            parse_internal("code {0}}}"),
+            ("code {__0}}}".to_string(), to_set(&["__0"]))
+        );
+        assert_eq!(
+            parse_internal("{{{0}}}"),
+            ("{{{__0}}}".to_string(), to_set(&["__0"]))
+        );
+
+        let (_, segments, _) = crate::parse_internal("{{{0}}}", Delimiters::default(), &[]);
+        assert_eq!(segments[0], Segment::Literal("{".to_string()));
+        assert_eq!(segments[2], Segment::Literal("}".to_string()));
+    }
+
+    #[test]
+    fn test_parse_precision() {
+        assert_eq!(
+            parse_internal("{0:.3} {0:.0} {0:>8.2e} {0:.prec$} {0:.1$} {0:.>6}"),
+            (
+                "{__0:.3} {__0:.0} {__0:>8.2e} {__0:.prec$} {__0:.__1$} {__0:.>6}".to_string(),
+                to_set(&["__0"])
+            )
+        );
+
+        let (_, _, errors) = crate::parse_internal("value: {0:.x}", Delimiters::default(), &[]);
+        assert_eq!(
+            errors,
+            [crate::ParseError::InvalidPrecision {
+                offset: 7,
+                spec: ".x".to_string()
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Invalid precision in the format spec `.x` at offset 7, expected a number e.g. `.3`, \
+             or a count argument e.g. `.prec$`"
+        );
+
+        let (_, _, errors) =
+            crate::parse_internal("{:>8.} {:.?} {:.*}", Delimiters::default(), &[]);
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_raw_identifiers() {
+        assert_eq!(
+            parse_internal("{r#type} {r#type:?} {r#type:path}"),
+            (
+                "{__r_type} {__r_type:?} {__path_r_type}".to_string(),
+                to_set(&["r#type"])
+            )
+        );
+    }
 }
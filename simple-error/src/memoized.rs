@@ -0,0 +1,55 @@
+use std::{error::Error, fmt, sync::OnceLock};
+
+/// An error whose message is rendered once, the first time it's displayed, and then
+/// reused, e.g. for errors whose message is expensive to render and displayed many times.
+///
+/// It returns the same `source()` as the wrapped error, so it can stand in for it.
+pub struct Memoized<E> {
+    error: E,
+    message: OnceLock<String>,
+}
+
+impl<E> Memoized<E> {
+    /// Wrap the error, without rendering its message yet.
+    pub fn new(error: E) -> Self {
+        Self {
+            error,
+            message: OnceLock::new(),
+        }
+    }
+
+    /// The wrapped error.
+    pub fn get_ref(&self) -> &E {
+        &self.error
+    }
+
+    /// Unwrap the error, dropping its rendered message.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> Memoized<E> {
+    /// The message of the error, rendering it if it's the first time.
+    pub fn message(&self) -> &str {
+        self.message.get_or_init(|| self.error.to_string())
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for Memoized<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Memoized<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl<E: Error> Error for Memoized<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
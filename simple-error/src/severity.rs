@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// The severity of an error, as set by `#[error("...", severity = "warn")]`, e.g. to
+/// route errors to the matching log level.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    /// The severity of the variants which don't set one.
+    #[default]
+    Error,
+}
+
+impl Severity {
+    /// Every severity, from the least to the most severe.
+    pub const ALL: [Self; 5] = [
+        Self::Trace,
+        Self::Debug,
+        Self::Info,
+        Self::Warn,
+        Self::Error,
+    ];
+
+    /// The severity with the given name, e.g. `warn`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|severity| severity.name() == name)
+    }
+
+    /// The name of the severity, as written in `#[error(...)]`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}